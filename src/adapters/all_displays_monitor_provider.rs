@@ -0,0 +1,16 @@
+use crate::{models::monitor::Monitor, ports::MonitorProviderPort};
+
+use anyhow::Result;
+
+pub const ALL_DISPLAYS: &str = "All displays";
+
+// Used by backends that can't set a wallpaper per monitor
+// (e.g. GNOME-like desktops and feh), so every monitor
+// is collapsed into a single target.
+pub struct AllDisplaysMonitorProvider;
+
+impl MonitorProviderPort for AllDisplaysMonitorProvider {
+    fn get_monitors(&self) -> Result<Vec<Monitor>> {
+        Ok(vec![Monitor::new(ALL_DISPLAYS)])
+    }
+}
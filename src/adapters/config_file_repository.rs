@@ -0,0 +1,67 @@
+use anyhow::{Context, Result};
+use std::{fs, path::PathBuf};
+
+use crate::{adapters::utils, models::config::Config, ports::ConfigRepository};
+
+pub struct ConfigFileRepository {
+    path: PathBuf,
+    optional: bool, // Only the default file may be missing
+}
+
+impl ConfigFileRepository {
+    // A file the user asked for, so it must exist
+    pub fn new(path: impl Into<PathBuf>) -> Self {
+        ConfigFileRepository {
+            path: path.into(),
+            optional: false,
+        }
+    }
+
+    // $XDG_CONFIG_HOME/walrust/config.toml, or ~/.config/walrust/config.toml
+    pub fn from_default_path() -> Result<Self> {
+        Ok(ConfigFileRepository {
+            path: utils::config_dir()?.join("config.toml"),
+            optional: true,
+        })
+    }
+
+    pub fn path(&self) -> &PathBuf {
+        &self.path
+    }
+
+    // Whether load returns the default config because there is no file
+    pub fn uses_defaults(&self) -> bool {
+        self.optional && !self.path.exists()
+    }
+}
+
+impl ConfigRepository for ConfigFileRepository {
+    fn load(&self) -> Result<Config> {
+        if self.uses_defaults() {
+            return Ok(Config::default());
+        }
+
+        let content = fs::read_to_string(&self.path)
+            .with_context(|| format!("Failed to read {:?}", self.path))?;
+
+        let mut config: Config =
+            toml::from_str(&content).with_context(|| format!("Failed to parse {:?}", self.path))?;
+        config
+            .validate()
+            .with_context(|| format!("Invalid config {:?}", self.path))?;
+
+        for dir in [
+            &mut config.directories.default,
+            &mut config.directories.pick,
+            &mut config.directories.random,
+            &mut config.directories.daemon,
+        ]
+        .into_iter()
+        .flatten()
+        {
+            *dir = utils::expand_home(dir);
+        }
+
+        Ok(config)
+    }
+}
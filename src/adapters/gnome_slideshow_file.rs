@@ -0,0 +1,167 @@
+use anyhow::{Context, Result, anyhow, ensure};
+use chrono::{NaiveDate, NaiveDateTime, TimeDelta};
+use roxmltree::{Document, Node};
+use std::{
+    fs,
+    path::{Path, PathBuf},
+};
+
+use crate::models::slideshow::{Slide, Slideshow};
+
+// A slide longer than a year is most likely a broken file
+const MAX_DURATION_SECONDS: f64 = 365.0 * 24.0 * 60.0 * 60.0;
+
+/*
+ * GnomeSlideshowFile reads the <background> XML files of GNOME timed wallpapers:
+ *
+ * <background>
+ *   <starttime><year>2011</year><month>11</month><day>24</day><hour>7</hour>...</starttime>
+ *   <static><duration>1795.0</duration><file>morning.jpg</file></static>
+ *   <transition type="overlay"><duration>5.0</duration><from>morning.jpg</from><to>noon.jpg</to></transition>
+ *   ...
+ * </background>
+ *
+ * Backends can't blend two images, so a transition switches halfway through.
+ */
+
+pub struct GnomeSlideshowFile {
+    path: PathBuf,
+}
+
+impl GnomeSlideshowFile {
+    pub fn new(path: impl Into<PathBuf>) -> Self {
+        GnomeSlideshowFile { path: path.into() }
+    }
+
+    pub fn is_slideshow(path: &Path) -> bool {
+        path.is_file()
+            && path
+                .extension()
+                .and_then(|ext| ext.to_str())
+                .is_some_and(|ext| ext.eq_ignore_ascii_case("xml"))
+    }
+
+    pub fn load(&self) -> Result<Slideshow> {
+        let content = fs::read_to_string(&self.path)
+            .with_context(|| format!("Failed to read {:?}", self.path))?;
+
+        self.parse(&content)
+            .with_context(|| format!("Failed to parse {:?}", self.path))
+    }
+
+    fn parse(&self, content: &str) -> Result<Slideshow> {
+        let document = Document::parse(content)?;
+        let background = document.root_element();
+        ensure!(
+            background.has_tag_name("background"),
+            "Not a GNOME slideshow, expected <background>"
+        );
+
+        let mut slides: Vec<Slide> = Vec::new();
+        let mut start = NaiveDateTime::default();
+
+        for node in background.children().filter(Node::is_element) {
+            match node.tag_name().name() {
+                "starttime" => start = parse_start_time(node)?,
+                "static" => {
+                    let path = self.image_path(child(node, "file")?)?;
+                    push_slide(&mut slides, path, parse_duration(node)?);
+                }
+                "transition" => {
+                    let half = parse_duration(node)? / 2;
+                    let from = self.image_path(child(node, "from")?)?;
+                    let to = self.image_path(child(node, "to")?)?;
+                    push_slide(&mut slides, from, half);
+                    push_slide(&mut slides, to, half);
+                }
+                _ => {}
+            }
+        }
+
+        ensure!(!slides.is_empty(), "The slideshow has no images");
+
+        Ok(Slideshow { start, slides })
+    }
+
+    // A file is either a path, or a list of <size> elements for different resolutions
+    fn image_path(&self, node: Node) -> Result<PathBuf> {
+        let largest_size = node
+            .children()
+            .filter(|child| child.has_tag_name("size"))
+            .max_by_key(|size| {
+                let dimension = |name| {
+                    size.attribute(name)
+                        .and_then(|value| value.parse::<u64>().ok())
+                        .unwrap_or(0)
+                };
+                dimension("width") * dimension("height")
+            });
+
+        let text = largest_size
+            .unwrap_or(node)
+            .text()
+            .map(str::trim)
+            .filter(|text| !text.is_empty())
+            .ok_or(anyhow!("<{}> has no path", node.tag_name().name()))?;
+
+        // Relative paths are relative to the XML file
+        Ok(match self.path.parent() {
+            Some(parent) => parent.join(text),
+            None => PathBuf::from(text),
+        })
+    }
+}
+
+// Consecutive slides of the same image are merged
+fn push_slide(slides: &mut Vec<Slide>, path: PathBuf, duration: TimeDelta) {
+    match slides.last_mut() {
+        Some(last) if last.path == path => last.duration += duration,
+        _ => slides.push(Slide { path, duration }),
+    }
+}
+
+fn child<'a, 'input>(node: Node<'a, 'input>, name: &str) -> Result<Node<'a, 'input>> {
+    node.children()
+        .find(|child| child.has_tag_name(name))
+        .ok_or(anyhow!("<{}> has no <{}>", node.tag_name().name(), name))
+}
+
+fn child_number(node: Node, name: &str) -> Result<f64> {
+    let text = child(node, name)?.text().unwrap_or_default().trim();
+
+    text.parse()
+        .with_context(|| format!("Invalid <{}>: {}", name, text))
+}
+
+// Durations are seconds, with a fraction.
+// Longer ones would overflow once the slides are added up.
+fn parse_duration(node: Node) -> Result<TimeDelta> {
+    let seconds = child_number(node, "duration")?;
+    ensure!(
+        seconds.is_finite(),
+        "Duration must be a number: {}",
+        seconds
+    );
+    ensure!(seconds >= 0.0, "Duration must not be negative");
+    ensure!(
+        seconds <= MAX_DURATION_SECONDS,
+        "Duration must be at most a year ({} seconds)",
+        MAX_DURATION_SECONDS
+    );
+
+    TimeDelta::try_milliseconds((seconds * 1000.0).round() as i64)
+        .ok_or(anyhow!("Invalid duration: {}", seconds))
+}
+
+fn parse_start_time(node: Node) -> Result<NaiveDateTime> {
+    let number = |name| child_number(node, name).map(|value| value as u32);
+    let (hour, minute, second) = (number("hour")?, number("minute")?, number("second")?);
+
+    NaiveDate::from_ymd_opt(
+        child_number(node, "year")? as i32,
+        number("month")?,
+        number("day")?,
+    )
+    .and_then(|date| date.and_hms_opt(hour, minute, second))
+    .ok_or(anyhow!("Invalid <starttime>"))
+}
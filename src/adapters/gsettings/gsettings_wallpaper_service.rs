@@ -0,0 +1,109 @@
+use anyhow::{Context, Result, ensure};
+use std::path::Path;
+
+use crate::{
+    adapters::utils::file_uri,
+    models::{fit_mode::FitMode, image_format::ImageFormat, monitor::Monitor},
+    ports::wallpaper_service_port::WallpaperServicePort,
+};
+
+use super::utils;
+
+// Each desktop stores its wallpaper in its own schema
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum GsettingsSchema {
+    Gnome,    // org.gnome.desktop.background, picture-uri and picture-uri-dark
+    Cinnamon, // org.cinnamon.desktop.background, picture-uri
+    Mate,     // org.mate.background, picture-filename (a plain path, not an URI)
+}
+
+impl GsettingsSchema {
+    fn id(&self) -> &'static str {
+        match self {
+            Self::Gnome => "org.gnome.desktop.background",
+            Self::Cinnamon => "org.cinnamon.desktop.background",
+            Self::Mate => "org.mate.background",
+        }
+    }
+}
+
+/*
+ * GNOME-like desktops have a single wallpaper for every display,
+ * so the monitor name is ignored.
+ */
+
+pub struct GsettingsWallpaperService {
+    schema: GsettingsSchema,
+    fit_mode: FitMode,
+}
+
+impl GsettingsWallpaperService {
+    pub fn new(schema: GsettingsSchema) -> Self {
+        GsettingsWallpaperService {
+            schema,
+            fit_mode: FitMode::default(),
+        }
+    }
+
+    pub fn with_fit_mode(mut self, fit_mode: FitMode) -> Self {
+        self.fit_mode = fit_mode;
+        self
+    }
+
+    fn picture_options(&self) -> &'static str {
+        match self.fit_mode {
+            FitMode::Fill => "zoom",
+            FitMode::Fit => "scaled",
+            FitMode::Stretch => "stretched",
+            FitMode::Center => "centered",
+            FitMode::Tile => "wallpaper",
+        }
+    }
+
+    fn set(&self, key: &str, value: &str) -> Result<()> {
+        let schema = self.schema.id();
+
+        let _ = utils::gsettings(&["set", schema, key, &utils::quote(value)])
+            .with_context(|| format!("Failed to set {} {}", schema, key))?;
+
+        Ok(())
+    }
+}
+
+impl WallpaperServicePort for GsettingsWallpaperService {
+    // The desktops load images with gdk-pixbuf, the other formats are converted to PNG
+    fn supported_formats(&self) -> &'static [ImageFormat] {
+        &[
+            ImageFormat::Png,
+            ImageFormat::Jpeg,
+            ImageFormat::Webp,
+            ImageFormat::Gif,
+            ImageFormat::Bmp,
+            ImageFormat::Tiff,
+        ]
+    }
+
+    fn set_wallpaper(&self, _monitor: &Monitor, path: &Path) -> Result<()> {
+        match self.schema {
+            GsettingsSchema::Gnome => {
+                let uri = file_uri(path);
+                self.set("picture-uri", &uri)?;
+                self.set("picture-uri-dark", &uri)?;
+            }
+            GsettingsSchema::Cinnamon => {
+                self.set("picture-uri", &file_uri(path))?;
+            }
+            GsettingsSchema::Mate => {
+                // Unlike the URIs, the file name is a string that can't hold other bytes
+                ensure!(
+                    path.to_str().is_some(),
+                    "MATE can't set a wallpaper with a non UTF-8 path: {:?}",
+                    path
+                );
+                self.set("picture-filename", &path.display().to_string())?;
+            }
+        }
+
+        self.set("picture-options", self.picture_options())
+    }
+}
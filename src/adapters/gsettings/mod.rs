@@ -0,0 +1,4 @@
+mod gsettings_wallpaper_service;
+mod utils;
+
+pub use gsettings_wallpaper_service::*;
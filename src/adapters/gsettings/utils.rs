@@ -0,0 +1,22 @@
+use anyhow::{Context, Result, ensure};
+use std::process::{Command, Output};
+
+pub fn gsettings(args: &[&str]) -> Result<Output> {
+    let output = Command::new("gsettings")
+        .args(args)
+        .output()
+        .context("Failed to run gsettings command")?;
+
+    ensure!(
+        output.status.success(),
+        "gsettings command failed and returned: {}",
+        String::from_utf8_lossy(&output.stderr).trim()
+    );
+
+    Ok(output)
+}
+
+// gsettings parses the value as a GVariant, so strings must be quoted.
+pub fn quote(value: &str) -> String {
+    format!("'{}'", value.replace('\\', "\\\\").replace('\'', "\\'"))
+}
@@ -0,0 +1,70 @@
+use std::{
+    env,
+    io::{Read, Write},
+    os::unix::net::UnixStream,
+    path::PathBuf,
+    time::Duration,
+};
+
+use anyhow::{Context, Result};
+
+/*
+ * HyprSocket talks to Hyprland and Hyprpaper through their IPC sockets directly,
+ * so walrust doesn't need to spawn a hyprctl process for every request.
+ * Both use the same protocol: write the command, then read the reply until
+ * the other side closes the connection.
+ */
+
+pub struct HyprSocket {
+    path: PathBuf,
+}
+
+impl HyprSocket {
+    pub fn new(path: impl Into<PathBuf>) -> Self {
+        HyprSocket { path: path.into() }
+    }
+
+    // Socket used for Hyprland requests, e.g. "j/monitors"
+    pub fn hyprland() -> Option<Self> {
+        Self::from_env(".socket.sock")
+    }
+
+    // Socket used for Hyprpaper requests, e.g. "preload /path/to/image.png"
+    pub fn hyprpaper() -> Option<Self> {
+        Self::from_env(".hyprpaper.sock")
+    }
+
+    // $XDG_RUNTIME_DIR/hypr/$HYPRLAND_INSTANCE_SIGNATURE/<socket_name>
+    fn from_env(socket_name: &str) -> Option<Self> {
+        let runtime_dir = env::var_os("XDG_RUNTIME_DIR")?;
+        let instance_signature = env::var_os("HYPRLAND_INSTANCE_SIGNATURE")?;
+
+        let path = PathBuf::from(runtime_dir)
+            .join("hypr")
+            .join(instance_signature)
+            .join(socket_name);
+
+        path.exists().then(|| Self::new(path))
+    }
+
+    pub fn connect(&self) -> Result<UnixStream> {
+        UnixStream::connect(&self.path)
+            .with_context(|| format!("Failed to connect to {:?}", self.path))
+    }
+
+    // Writes the command to a connected stream and reads the whole reply
+    pub fn send(&self, mut stream: UnixStream, command: &str) -> Result<String> {
+        stream.set_read_timeout(Some(Duration::from_secs(5)))?;
+
+        stream
+            .write_all(command.as_bytes())
+            .with_context(|| format!("Failed to write to {:?}", self.path))?;
+
+        let mut reply = String::new();
+        stream
+            .read_to_string(&mut reply)
+            .with_context(|| format!("Failed to read from {:?}", self.path))?;
+
+        Ok(reply)
+    }
+}
@@ -1,39 +1,48 @@
-use crate::ports::MonitorProviderPort;
+use crate::{
+    models::monitor::{Monitor, MonitorGeometry},
+    ports::MonitorProviderPort,
+};
 
-use anyhow::{Context, Result, anyhow, ensure};
+use anyhow::{Context, Result, anyhow};
 use serde_json::Value;
 
 pub struct HyprctlMonitorProvider;
 
+impl HyprctlMonitorProvider {
+    fn parse_monitor(monitor: &Value) -> Option<Monitor> {
+        let name = monitor["name"].as_str()?;
+
+        let geometry = MonitorGeometry {
+            width: monitor["width"].as_u64().unwrap_or_default() as u32,
+            height: monitor["height"].as_u64().unwrap_or_default() as u32,
+            x: monitor["x"].as_i64().unwrap_or_default() as i32,
+            y: monitor["y"].as_i64().unwrap_or_default() as i32,
+            scale: monitor["scale"].as_f64().unwrap_or(1.0),
+            transform: monitor["transform"].as_u64().unwrap_or_default() as u8,
+        };
+
+        Some(
+            Monitor::new(name)
+                .with_description(monitor["description"].as_str().unwrap_or_default())
+                .with_geometry(geometry)
+                .with_focused(monitor["focused"].as_bool().unwrap_or_default()),
+        )
+    }
+}
+
 impl MonitorProviderPort for HyprctlMonitorProvider {
-    fn get_monitors(&self) -> Result<Vec<String>> {
-        let arg = "monitors"; // Lists active output or monitor
-        let flag = "-j"; // Output in JSON
-
-        let output = super::utils::hyprctl(&[arg, flag])?;
-
-        ensure!(
-            output.status.success(),
-            "hyprctl command failed and returned: {}",
-            if output.stderr.is_empty() {
-                String::from_utf8_lossy(&output.stdout)
-            } else {
-                String::from_utf8_lossy(&output.stderr)
-            }
-        );
-
-        let stdout_utf8 = String::from_utf8_lossy(&output.stdout);
-        let monitors: Value = serde_json::from_str(&stdout_utf8)
-            .context("Failed to convert hyprctl output to JSON")?;
+    fn get_monitors(&self) -> Result<Vec<Monitor>> {
+        // Lists active output or monitor in JSON
+        let reply = super::utils::hyprland_request("j/monitors", &["monitors", "-j"])?;
+
+        let monitors: Value =
+            serde_json::from_str(&reply).context("Failed to convert hyprctl output to JSON")?;
 
         if let Some(monitors_array) = monitors.as_array() {
-            let name_properties = "name";
-            let monitor_names: Vec<String> = monitors_array
+            Ok(monitors_array
                 .iter()
-                .filter_map(|monitor| monitor[name_properties].as_str())
-                .map(|s| s.to_owned())
-                .collect();
-            Ok(monitor_names)
+                .filter_map(Self::parse_monitor)
+                .collect())
         } else {
             Err(anyhow!("hyprctl command returned nothing or not an array"))
         }
@@ -1,41 +1,176 @@
-use anyhow::{Context, Result, ensure};
-use std::path::Path;
+use anyhow::{Context, Result, anyhow, ensure};
+use std::{
+    collections::HashMap,
+    path::{Path, PathBuf},
+    sync::{Mutex, MutexGuard},
+};
 
-use crate::ports::wallpaper_service_port::WallpaperServicePort;
+use crate::{
+    models::{image_format::ImageFormat, monitor::Monitor},
+    ports::wallpaper_service_port::WallpaperServicePort,
+};
 
-use super::utils;
+use super::{HyprpaperConfig, HyprpaperPersistence, utils};
 
-pub struct HyprctlWallpaperService;
+/*
+ * Hyprpaper keeps every preloaded image in memory until it's unloaded.
+ * Unloading everything before changing one monitor can blank the other monitors,
+ * so HyprctlWallpaperService keeps track of which image is shown on which monitor
+ * and only unloads images that no monitor uses anymore.
+ */
+
+#[derive(Default)]
+pub struct HyprctlWallpaperService {
+    // Monitor name -> image path.
+    // None until it's filled from `hyprpaper listactive` on the first change.
+    active_wallpapers: Mutex<Option<HashMap<String, PathBuf>>>,
+
+    // Whether the wallpaper is also written to hyprpaper.conf
+    persistence: HyprpaperPersistence,
+}
+
+impl HyprctlWallpaperService {
+    pub fn with_persistence(mut self, persistence: HyprpaperPersistence) -> Self {
+        self.persistence = persistence;
+        self
+    }
+
+    fn active_wallpapers(&self) -> Result<MutexGuard<'_, Option<HashMap<String, PathBuf>>>> {
+        let mut active_wallpapers = self
+            .active_wallpapers
+            .lock()
+            .map_err(|_| anyhow!("Active wallpapers lock is poisoned"))?;
+
+        if active_wallpapers.is_none() {
+            *active_wallpapers = Some(
+                Self::list_active().context("Failed to list the active hyprpaper wallpapers")?,
+            );
+        }
+
+        Ok(active_wallpapers)
+    }
+
+    // `hyprpaper listactive` replies one "monitor = path" per line
+    fn list_active() -> Result<HashMap<String, PathBuf>> {
+        let reply = utils::hyprpaper_request(&["listactive"])?;
+
+        Ok(reply
+            .lines()
+            .filter_map(|line| line.split_once('='))
+            .map(|(monitor, path)| (monitor.trim().to_owned(), PathBuf::from(path.trim())))
+            .filter(|(monitor, _)| !monitor.is_empty())
+            .collect())
+    }
+
+    // Older hyprpaper versions don't support `unload unused`,
+    // so the image is unloaded by its path instead.
+    fn unload(path: &Path) -> Result<()> {
+        let reply = utils::hyprpaper_request(&["unload", "unused"])?;
+        if Self::is_ok(&reply) {
+            return Ok(());
+        }
+
+        let path_string = path.display().to_string();
+        let reply = utils::hyprpaper_request(&["unload", &path_string])?;
+
+        ensure!(
+            Self::is_ok(&reply),
+            "Failed to unload {} and returned: {}",
+            path_string,
+            reply.trim()
+        );
+
+        Ok(())
+    }
+
+    fn persist(&self, monitor: &Monitor, path: &Path) -> Result<()> {
+        if self.persistence == HyprpaperPersistence::Disabled {
+            return Ok(());
+        }
+
+        let config = HyprpaperConfig::new(HyprpaperConfig::default_path()?);
+
+        match self.persistence {
+            HyprpaperPersistence::Write => config
+                .persist(monitor, path)
+                .context("Failed to persist wallpaper to hyprpaper config"),
+            HyprpaperPersistence::DryRun => {
+                print!("{}", config.diff(monitor, path)?);
+                Ok(())
+            }
+            HyprpaperPersistence::Disabled => Ok(()),
+        }
+    }
+
+    // Hyprpaper replies "ok" on success, otherwise the error message.
+    // An empty reply means the request wasn't handled.
+    fn is_ok(reply: &str) -> bool {
+        reply.trim() == "ok"
+    }
+}
 
 impl WallpaperServicePort for HyprctlWallpaperService {
-    fn set_wallpaper(&self, monitor_name: &str, path: &Path) -> Result<()> {
+    // Formats hyprpaper can display, the others are converted to PNG
+    fn supported_formats(&self) -> &'static [ImageFormat] {
+        &[
+            ImageFormat::Png,
+            ImageFormat::Jpeg,
+            ImageFormat::Webp,
+            ImageFormat::Jxl,
+        ]
+    }
+
+    fn set_wallpaper(&self, monitor: &Monitor, path: &Path) -> Result<()> {
         let command = "hyprpaper";
 
-        let _ = utils::hyprctl(&[command, "unload", "all"])
-            .with_context(|| format!("Failed to unload all {} images", command))?;
+        // Hyprpaper requests are text, a lossy path would be another file
+        ensure!(
+            path.to_str().is_some(),
+            "{} can't set a wallpaper with a non UTF-8 path: {:?}",
+            command,
+            path
+        );
+
+        let mut active_wallpapers = self.active_wallpapers()?;
+        let active_wallpapers = active_wallpapers.get_or_insert_default();
 
         let path_string = path.display().to_string();
 
-        let _ = utils::hyprctl(&[command, "preload", &path_string])
-            .with_context(|| format!("Failed to preload {} to {}", &path_string, command))?;
+        // The image is already in memory if another monitor shows it
+        if !active_wallpapers.values().any(|active| active == path) {
+            let reply = utils::hyprpaper_request(&["preload", &path_string])
+                .with_context(|| format!("Failed to preload {} to {}", &path_string, command))?;
 
-        let change_wallpaper_output = utils::hyprctl(&[
-            command,
-            "wallpaper",
-            &format!("{}, {}", monitor_name, path_string),
-        ])
-        .context("Failed to change wallpaper")?;
+            ensure!(
+                Self::is_ok(&reply),
+                "Failed to preload {} to {} and returned: {}",
+                path_string,
+                command,
+                reply.trim()
+            );
+        }
+
+        let reply =
+            utils::hyprpaper_request(&["wallpaper", &format!("{}, {}", monitor.name, path_string)])
+                .context("Failed to change wallpaper")?;
 
         ensure!(
-            change_wallpaper_output.status.success(),
-            "hyprctl command failed and returned: {}",
-            if change_wallpaper_output.stderr.is_empty() {
-                String::from_utf8_lossy(&change_wallpaper_output.stdout)
-            } else {
-                String::from_utf8_lossy(&change_wallpaper_output.stderr)
-            }
+            Self::is_ok(&reply),
+            "{} failed and returned: {}",
+            command,
+            reply.trim()
         );
 
-        Ok(())
+        let previous = active_wallpapers.insert(monitor.name.clone(), path.to_path_buf());
+
+        if let Some(previous) = previous
+            && previous != path
+            && !active_wallpapers.values().any(|active| *active == previous)
+        {
+            Self::unload(&previous)
+                .with_context(|| format!("Failed to unload unused {} images", command))?;
+        }
+
+        self.persist(monitor, path)
     }
 }
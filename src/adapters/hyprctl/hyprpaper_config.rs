@@ -0,0 +1,295 @@
+use std::{
+    env, fs,
+    path::{Path, PathBuf},
+};
+
+use anyhow::{Context, Result, anyhow};
+
+use crate::models::monitor::Monitor;
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum HyprpaperPersistence {
+    #[default]
+    Disabled,
+    Write,
+    DryRun, // Print the diff instead of writing the config
+}
+
+impl HyprpaperPersistence {
+    pub fn from_env(env_var: &str) -> Result<Self> {
+        match env::var(env_var).as_deref() {
+            Err(_) | Ok("") | Ok("0") | Ok("false") => Ok(Self::Disabled),
+            Ok("1") | Ok("true") | Ok("write") => Ok(Self::Write),
+            Ok("dry-run") => Ok(Self::DryRun),
+            Ok(value) => Err(anyhow!(
+                "Invalid value for {}: {}, expected true, false or dry-run",
+                env_var,
+                value
+            )),
+        }
+    }
+}
+
+/*
+ * HyprpaperConfig rewrites hyprpaper.conf so the wallpaper set by walrust
+ * is still there on the next login.
+ * Only the `preload =` and `wallpaper =` lines of the changed monitor are touched,
+ * every other line (including comments) is kept as it is.
+ * Monitors are written with their `desc:` selector when they have a description,
+ * so the wallpaper stays with the physical screen when connector names change.
+ */
+
+pub struct HyprpaperConfig {
+    path: PathBuf,
+}
+
+impl HyprpaperConfig {
+    pub fn new(path: impl Into<PathBuf>) -> Self {
+        HyprpaperConfig { path: path.into() }
+    }
+
+    // $XDG_CONFIG_HOME/hypr/hyprpaper.conf, or ~/.config/hypr/hyprpaper.conf
+    pub fn default_path() -> Result<PathBuf> {
+        let config_dir = env::var_os("XDG_CONFIG_HOME")
+            .map(PathBuf::from)
+            .or_else(|| env::var_os("HOME").map(|home| PathBuf::from(home).join(".config")))
+            .ok_or(anyhow!("Neither XDG_CONFIG_HOME nor HOME is set"))?;
+
+        Ok(config_dir.join("hypr").join("hyprpaper.conf"))
+    }
+
+    pub fn persist(&self, monitor: &Monitor, image_path: &Path) -> Result<()> {
+        let content = self.read()?;
+        let updated = Self::update(&content, monitor, image_path);
+
+        if updated != content {
+            self.write(&updated)?;
+        }
+
+        Ok(())
+    }
+
+    // Written next to the file and renamed over it, so an interrupted write
+    // doesn't leave a truncated config. A symlinked config (e.g. from a dotfiles
+    // repository) is replaced at its target, with the same permissions.
+    fn write(&self, content: &str) -> Result<()> {
+        let path = fs::canonicalize(&self.path).unwrap_or_else(|_| self.path.clone());
+        let partial_path = path.with_extension("partial");
+
+        fs::write(&partial_path, content)
+            .with_context(|| format!("Failed to write {:?}", partial_path))?;
+        if let Ok(metadata) = fs::metadata(&path) {
+            fs::set_permissions(&partial_path, metadata.permissions())
+                .with_context(|| format!("Failed to write {:?}", partial_path))?;
+        }
+        fs::rename(&partial_path, &path).with_context(|| format!("Failed to write {:?}", path))
+    }
+
+    pub fn diff(&self, monitor: &Monitor, image_path: &Path) -> Result<String> {
+        let content = self.read()?;
+        let updated = Self::update(&content, monitor, image_path);
+
+        Ok(Self::diff_lines(&self.path, &content, &updated))
+    }
+
+    fn read(&self) -> Result<String> {
+        if !self.path.exists() {
+            return Ok(String::new());
+        }
+
+        fs::read_to_string(&self.path).with_context(|| format!("Failed to read {:?}", self.path))
+    }
+
+    fn update(content: &str, monitor: &Monitor, image_path: &Path) -> String {
+        let image = image_path.display().to_string();
+        let wallpaper_line = format!(
+            "wallpaper = {},{}",
+            Self::escape(&monitor.selector()),
+            Self::escape(&image)
+        );
+        // Lines keep their own line endings, new ones use the ending of the file
+        let newline = if content.contains("\r\n") {
+            "\r\n"
+        } else {
+            "\n"
+        };
+        let wallpaper_line = wallpaper_line + newline;
+        let preload_line = format!("preload = {}{}", Self::escape(&image), newline);
+
+        let mut lines: Vec<String> = content.split_inclusive('\n').map(str::to_owned).collect();
+        if let Some(last) = lines.last_mut()
+            && !last.ends_with('\n')
+        {
+            last.push_str(newline);
+        }
+
+        // Replace the wallpaper of the monitor, or add it
+        let mut previous_image = None;
+        match lines
+            .iter()
+            .position(|line| Self::wallpaper_value(line).is_some_and(|(m, _)| monitor.matches(&m)))
+        {
+            Some(index) => {
+                previous_image = Self::wallpaper_value(&lines[index]).map(|(_, i)| i);
+                lines[index] = wallpaper_line;
+            }
+            None => lines.push(wallpaper_line),
+        }
+
+        let is_used = |lines: &[String], image: &str| {
+            lines
+                .iter()
+                .any(|line| Self::wallpaper_value(line).is_some_and(|(_, i)| i == image))
+        };
+        let preload_index = |lines: &[String], image: &str| {
+            lines
+                .iter()
+                .position(|line| Self::preload_value(line).as_deref() == Some(image))
+        };
+
+        // Reuse the preload line of the previous image if nothing else uses it
+        if let Some(previous_image) = previous_image
+            && previous_image != image
+            && !is_used(&lines, &previous_image)
+            && let Some(index) = preload_index(&lines, &previous_image)
+        {
+            if preload_index(&lines, &image).is_some() {
+                lines.remove(index);
+            } else {
+                lines[index] = preload_line.clone();
+            }
+        }
+
+        // Preload lines must come before the wallpaper lines that use them
+        if preload_index(&lines, &image).is_none() {
+            let index = lines
+                .iter()
+                .position(|line| Self::wallpaper_value(line).is_some())
+                .unwrap_or(lines.len());
+            lines.insert(index, preload_line);
+        }
+
+        lines.concat()
+    }
+
+    // "wallpaper = monitor,path" -> Some(("monitor", "path"))
+    fn wallpaper_value(line: &str) -> Option<(String, String)> {
+        let value = Self::value(line, "wallpaper")?;
+        let (monitor, image) = value.split_once(',')?;
+
+        Some((monitor.trim().to_owned(), image.trim().to_owned()))
+    }
+
+    // "preload = path" -> Some("path")
+    fn preload_value(line: &str) -> Option<String> {
+        Self::value(line, "preload")
+    }
+
+    // Hyprlang starts a comment at "#" and reads "##" as a literal "#"
+    fn value(line: &str, key: &str) -> Option<String> {
+        let (line_key, raw_value) = line.split_once('=')?;
+
+        if line_key.trim() != key {
+            return None;
+        }
+
+        let mut value = String::new();
+        let mut chars = raw_value.chars().peekable();
+        while let Some(char) = chars.next() {
+            if char == '#' && chars.next_if_eq(&'#').is_none() {
+                break;
+            }
+            value.push(char);
+        }
+
+        Some(value.trim().to_owned())
+    }
+
+    fn escape(value: &str) -> String {
+        value.replace('#', "##")
+    }
+
+    // Line based diff using the longest common subsequence,
+    // good enough for a config file with a few dozen lines.
+    fn diff_lines(path: &Path, old: &str, new: &str) -> String {
+        let old_lines: Vec<&str> = old.lines().collect();
+        let new_lines: Vec<&str> = new.lines().collect();
+
+        let mut lcs = vec![vec![0usize; new_lines.len() + 1]; old_lines.len() + 1];
+        for i in (0..old_lines.len()).rev() {
+            for j in (0..new_lines.len()).rev() {
+                lcs[i][j] = if old_lines[i] == new_lines[j] {
+                    lcs[i + 1][j + 1] + 1
+                } else {
+                    lcs[i + 1][j].max(lcs[i][j + 1])
+                };
+            }
+        }
+
+        let mut diff = format!("--- {}\n+++ {}\n", path.display(), path.display());
+        let (mut i, mut j) = (0, 0);
+        while i < old_lines.len() || j < new_lines.len() {
+            if i < old_lines.len() && j < new_lines.len() && old_lines[i] == new_lines[j] {
+                i += 1;
+                j += 1;
+            } else if i < old_lines.len()
+                && (j == new_lines.len() || lcs[i + 1][j] >= lcs[i][j + 1])
+            {
+                diff.push_str(&format!("-{}\n", old_lines[i]));
+                i += 1;
+            } else {
+                diff.push_str(&format!("+{}\n", new_lines[j]));
+                j += 1;
+            }
+        }
+
+        diff
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn value_unescapes_and_strips_comments() {
+        assert_eq!(
+            HyprpaperConfig::preload_value("preload = /a/##1.png # comment").as_deref(),
+            Some("/a/#1.png")
+        );
+        assert_eq!(
+            HyprpaperConfig::wallpaper_value("wallpaper = DP-1,/a/b##c.png"),
+            Some((String::from("DP-1"), String::from("/a/b#c.png")))
+        );
+    }
+
+    #[test]
+    fn update_keeps_other_lines_untouched() {
+        let monitor = Monitor::new("DP-1");
+        let content = "# mine\r\nsplash = false\r\npreload = /a.png\r\nwallpaper = DP-1,/a.png";
+        let updated = HyprpaperConfig::update(content, &monitor, Path::new("/b.png"));
+
+        assert_eq!(
+            updated,
+            "# mine\r\nsplash = false\r\npreload = /b.png\r\nwallpaper = DP-1,/b.png\r\n"
+        );
+    }
+
+    #[test]
+    fn update_escapes_hashes() {
+        let monitor = Monitor::new("DP-1");
+        let updated = HyprpaperConfig::update("", &monitor, Path::new("/a/#1.png"));
+
+        assert_eq!(
+            updated,
+            "preload = /a/##1.png\nwallpaper = DP-1,/a/##1.png\n"
+        );
+
+        // Updating again finds the escaped lines instead of adding new ones
+        let updated_again = HyprpaperConfig::update(&updated, &monitor, Path::new("/a/#2.png"));
+        assert_eq!(
+            updated_again,
+            "preload = /a/##2.png\nwallpaper = DP-1,/a/##2.png\n"
+        );
+    }
+}
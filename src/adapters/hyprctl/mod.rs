@@ -1,6 +1,10 @@
+mod hypr_socket;
 mod hyprctl_monitor_provider;
 mod hyprctl_wallpaper_service;
+mod hyprpaper_config;
 mod utils;
 
+pub use hypr_socket::*;
 pub use hyprctl_monitor_provider::*;
 pub use hyprctl_wallpaper_service::*;
+pub use hyprpaper_config::*;
@@ -1,6 +1,8 @@
-use anyhow::{Context, Result};
+use anyhow::{Context, Result, ensure};
 use std::process::{Command, Output};
 
+use super::HyprSocket;
+
 // Hyprctl sends its error output to stdout instead of stderr.
 // So to get the error message, you can use the stdout only,
 // or you can handle both for example:
@@ -15,3 +17,131 @@ pub fn hyprctl(args: &[&str]) -> Result<Output> {
         .output()
         .context("Failed to run hyprctl command")
 }
+
+// Sends a request to Hyprland and returns the reply.
+// The IPC socket is used when it's available, hyprctl is used as a fallback.
+// The socket command has its flags as a prefix (e.g. "j/monitors"),
+// while hyprctl takes them as arguments (e.g. ["monitors", "-j"]).
+pub fn hyprland_request(socket_command: &str, hyprctl_args: &[&str]) -> Result<String> {
+    request(HyprSocket::hyprland(), socket_command, || {
+        hyprctl_reply(hyprctl_args)
+    })
+}
+
+// Same as hyprland_request but for Hyprpaper (e.g. "preload /path/to/image.png").
+pub fn hyprpaper_request(args: &[&str]) -> Result<String> {
+    let hyprctl_args: Vec<&str> = std::iter::once("hyprpaper")
+        .chain(args.iter().copied())
+        .collect();
+
+    request(HyprSocket::hyprpaper(), &args.join(" "), || {
+        hyprctl_reply(&hyprctl_args)
+    })
+}
+
+// The fallback only runs when the socket can't be connected to.
+// Once the command is written it may have been applied already,
+// so sending it again through hyprctl could apply it twice.
+fn request(
+    socket: Option<HyprSocket>,
+    socket_command: &str,
+    fallback: impl FnOnce() -> Result<String>,
+) -> Result<String> {
+    if let Some(socket) = socket
+        && let Ok(stream) = socket.connect()
+    {
+        return socket.send(stream, socket_command);
+    }
+
+    fallback()
+}
+
+fn hyprctl_reply(hyprctl_args: &[&str]) -> Result<String> {
+    let output = hyprctl(hyprctl_args)?;
+
+    ensure!(
+        output.status.success(),
+        "hyprctl command failed and returned: {}",
+        if output.stderr.is_empty() {
+            String::from_utf8_lossy(&output.stdout)
+        } else {
+            String::from_utf8_lossy(&output.stderr)
+        }
+    );
+
+    Ok(String::from_utf8_lossy(&output.stdout).into_owned())
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{
+        io::{Read, Write},
+        os::unix::net::UnixListener,
+        path::PathBuf,
+        thread::{self, JoinHandle},
+    };
+
+    use anyhow::bail;
+
+    use super::*;
+    use crate::test_utils::TempDir;
+
+    // Replies once like Hyprpaper, and returns the command it received
+    fn serve_once(path: &PathBuf, reply: &'static str) -> JoinHandle<String> {
+        let listener = UnixListener::bind(path).unwrap();
+
+        thread::spawn(move || {
+            let (mut stream, _) = listener.accept().unwrap();
+            let mut command = [0; 256];
+            let length = stream.read(&mut command).unwrap();
+            stream.write_all(reply.as_bytes()).unwrap();
+            String::from_utf8_lossy(&command[..length]).into_owned()
+        })
+    }
+
+    #[test]
+    fn request_uses_the_socket() {
+        let dir = TempDir::new("hypr");
+        let path = dir.join(".hyprpaper.sock");
+        let server = serve_once(&path, "ok");
+
+        let reply = request(Some(HyprSocket::new(&path)), "preload /a.png", || {
+            bail!("hyprctl should not run")
+        })
+        .unwrap();
+
+        assert_eq!(reply, "ok");
+        assert_eq!(server.join().unwrap(), "preload /a.png");
+    }
+
+    #[test]
+    fn error_reply_is_not_sent_again() {
+        let dir = TempDir::new("hypr");
+        let path = dir.join(".hyprpaper.sock");
+        let server = serve_once(&path, "wallpaper failed (not preloaded)");
+
+        let reply = request(
+            Some(HyprSocket::new(&path)),
+            "wallpaper DP-1,/a.png",
+            || bail!("hyprctl should not run"),
+        )
+        .unwrap();
+
+        assert_eq!(reply, "wallpaper failed (not preloaded)");
+        server.join().unwrap();
+    }
+
+    #[test]
+    fn connect_failure_falls_back_to_hyprctl() {
+        // Nothing listens on the path
+        let dir = TempDir::new("hypr");
+        let path = dir.join(".hyprpaper.sock");
+
+        let reply = request(Some(HyprSocket::new(&path)), "listactive", || {
+            Ok(String::from("from hyprctl"))
+        })
+        .unwrap();
+
+        assert_eq!(reply, "from hyprctl");
+    }
+}
@@ -1,58 +1,264 @@
-use anyhow::Result;
-use std::{fs, path::Path};
+use anyhow::{Result, ensure};
+use ignore::{Walk, WalkBuilder};
+use std::{
+    ffi::OsString,
+    path::{Path, PathBuf},
+};
 
-use crate::{models::image_file::ImageFile, ports::image_repository::ImageRepository};
+use crate::{
+    adapters::{GnomeSlideshowFile, ImageService},
+    models::{config::ImagesConfig, image_file::ImageFile, image_format::ImageFormat},
+    ports::{ImageServicePort, image_repository::ImageRepository},
+};
 
+// Ignore file with gitignore-style patterns, read in every scanned directory
+const IGNORE_FILE_NAME: &str = ".walrustignore";
+
+/*
+ * ImageDiskRepository lists the images of a directory, and of its subdirectories
+ * down to max_depth when recursive is enabled. The names are relative to the directory.
+ * Files are images when they have one of the extensions, or when their first bytes
+ * are of the same formats if detect_by_content is enabled.
+ */
+
+#[derive(Clone)]
 pub struct ImageDiskRepository {
+    image_service: ImageService,
     image_extensions: Vec<String>,
+    image_formats: Vec<ImageFormat>, // Formats of the extensions that can be detected
+    detect_by_content: bool,
+    check_headers: bool,
+    recursive: bool,
+    max_depth: usize,
+    follow_symlinks: bool,
+    hidden_directories: bool,
 }
 
 impl Default for ImageDiskRepository {
     fn default() -> Self {
-        Self {
-            image_extensions: vec![
-                "jpg".to_string(),
-                "jpeg".to_string(),
-                "png".to_string(),
-                "webp".to_string(),
-            ],
-        }
+        Self::new(&ImagesConfig::default())
     }
 }
 
 impl ImageDiskRepository {
+    pub fn new(config: &ImagesConfig) -> Self {
+        Self {
+            image_service: ImageService,
+            image_extensions: config
+                .extensions
+                .iter()
+                .map(|ext| ext.to_lowercase())
+                .collect(),
+            image_formats: config
+                .extensions
+                .iter()
+                .filter_map(|ext| ImageFormat::from_extension(ext))
+                .collect(),
+            detect_by_content: config.detect_by_content,
+            check_headers: config.check_headers,
+            recursive: config.recursive,
+            max_depth: config.max_depth,
+            follow_symlinks: config.follow_symlinks,
+            hidden_directories: config.hidden_directories,
+        }
+    }
+
+    fn list_slideshow_images(&self, path: &Path) -> Result<Vec<ImageFile>> {
+        let slideshow = GnomeSlideshowFile::new(path).load()?;
+        let mut images: Vec<ImageFile> = Vec::new();
+
+        for slide in slideshow.slides {
+            if images.iter().any(|image| image.path == slide.path) {
+                continue;
+            }
+
+            let file_name = slide.path.file_name().unwrap_or_default().to_owned();
+
+            images.push(self.image_file(file_name, slide.path));
+        }
+
+        Ok(images)
+    }
+
+    // Depth 1 is the entries of the directory itself
+    fn walker(&self, path: &Path, max_depth: usize) -> Walk {
+        let hidden_directories = self.hidden_directories;
+
+        // Only the .walrustignore files are honoured, not the git ones
+        WalkBuilder::new(path)
+            .max_depth(Some(max_depth))
+            .follow_links(self.follow_symlinks)
+            .hidden(false)
+            .parents(false)
+            .ignore(false)
+            .git_ignore(false)
+            .git_global(false)
+            .git_exclude(false)
+            .add_custom_ignore_filename(IGNORE_FILE_NAME)
+            .sort_by_file_name(|a, b| a.cmp(b))
+            .filter_entry(move |entry| {
+                hidden_directories
+                    || entry.depth() == 0
+                    || !entry
+                        .file_type()
+                        .is_some_and(|file_type| file_type.is_dir())
+                    || !entry.file_name().to_string_lossy().starts_with('.')
+            })
+            .build()
+    }
+
+    // The format is told by the extension, and by the content when it has none
+    fn image_file(&self, name: OsString, path: PathBuf) -> ImageFile {
+        let format = ImageFormat::from_path(&path).or_else(|| self.image_service.format(&path));
+        let corrupt = self.check_headers && self.image_service.dimensions(&path).is_err();
+
+        ImageFile {
+            format,
+            corrupt,
+            ..ImageFile::new(name, path)
+        }
+    }
+
     fn is_supported_image(&self, path: &Path) -> bool {
-        path.extension()
+        let has_extension = path
+            .extension()
             .and_then(|ext| ext.to_str())
-            .map(|ext| self.image_extensions.contains(&ext.to_lowercase()))
-            .unwrap_or(false)
+            .is_some_and(|ext| self.image_extensions.contains(&ext.to_lowercase()));
+
+        has_extension || (self.detect_by_content && self.has_image_content(path))
+    }
+
+    fn has_image_content(&self, path: &Path) -> bool {
+        self.image_service
+            .format(path)
+            .is_some_and(|format| self.image_formats.contains(&format))
     }
 }
 
 impl ImageRepository for ImageDiskRepository {
+    // A GNOME slideshow XML file lists the images it references
     fn list_images(&self, path: &Path) -> Result<Vec<ImageFile>> {
-        let dir = fs::read_dir(path)?;
+        if GnomeSlideshowFile::is_slideshow(path) {
+            return self.list_slideshow_images(path);
+        }
+
+        ensure!(path.is_dir(), "{:?} is not a directory", path);
+
+        let max_depth = if self.recursive {
+            self.max_depth + 1
+        } else {
+            1
+        };
         let mut images: Vec<ImageFile> = Vec::new();
 
-        for file_result in dir {
-            let file = file_result?;
-            let file_type = file.file_type()?;
+        for entry_result in self.walker(path, max_depth) {
+            // Unreadable subdirectories and symlink loops are skipped
+            let Ok(entry) = entry_result else {
+                continue;
+            };
 
-            if !file_type.is_file() {
+            if !entry
+                .file_type()
+                .is_some_and(|file_type| file_type.is_file())
+            {
                 continue;
             }
 
-            if !self.is_supported_image(&file.path()) {
+            if !self.is_supported_image(entry.path()) {
                 continue;
             }
 
-            let file_name = file.file_name();
-            let file_name_str = file_name.to_str().unwrap();
-            let file_path = file.path();
+            let name = entry
+                .path()
+                .strip_prefix(path)
+                .unwrap_or(entry.path())
+                .as_os_str()
+                .to_owned();
 
-            images.push(ImageFile::new(file_name_str.to_owned(), file_path));
+            images.push(self.image_file(name, entry.into_path()));
         }
 
         Ok(images)
     }
+
+    // The subdirectories a recursive scan would enter, a slideshow has none
+    fn list_directories(&self, path: &Path) -> Result<Vec<PathBuf>> {
+        if GnomeSlideshowFile::is_slideshow(path) {
+            return Ok(Vec::new());
+        }
+
+        ensure!(path.is_dir(), "{:?} is not a directory", path);
+
+        Ok(self
+            .walker(path, 1)
+            .filter_map(|entry_result| entry_result.ok())
+            .filter(|entry| entry.depth() == 1)
+            .filter(|entry| {
+                entry
+                    .file_type()
+                    .is_some_and(|file_type| file_type.is_dir())
+            })
+            .map(|entry| entry.into_path())
+            .collect())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{fs, os::unix::ffi::OsStringExt};
+
+    use super::*;
+    use crate::test_utils::TempDir;
+
+    const PNG_MAGIC: &[u8] = b"\x89PNG\r\n\x1a\n\0\0\0\rIHDR";
+
+    fn os(bytes: &[u8]) -> OsString {
+        OsString::from_vec(bytes.to_vec())
+    }
+
+    // A fresh directory with images whose names aren't valid UTF-8
+    fn non_utf8_dir() -> TempDir {
+        let dir = TempDir::new("images");
+        fs::create_dir_all(dir.join(os(b"sub\xff"))).unwrap();
+
+        fs::write(dir.join(os(b"caf\xe9.png")), PNG_MAGIC).unwrap();
+        fs::write(dir.join(os(b"no-extension\xe9")), PNG_MAGIC).unwrap();
+        fs::write(dir.join(os(b"sub\xff")).join(os(b"\xfe.jpg")), PNG_MAGIC).unwrap();
+
+        dir
+    }
+
+    #[test]
+    fn non_utf8_names_and_paths_are_kept() {
+        let dir = non_utf8_dir();
+        let images = ImageDiskRepository::default().list_images(&dir).unwrap();
+
+        let names: Vec<&OsString> = images.iter().map(|image| &image.name).collect();
+        assert_eq!(names, [&os(b"caf\xe9.png"), &os(b"no-extension\xe9")]);
+
+        for image in &images {
+            assert_eq!(image.path, dir.join(&image.name));
+            assert!(image.path.is_file());
+        }
+    }
+
+    #[test]
+    fn non_utf8_subdirectories_are_kept() {
+        let dir = non_utf8_dir();
+        let repository = ImageDiskRepository::new(&ImagesConfig {
+            recursive: true,
+            ..ImagesConfig::default()
+        });
+        let images = repository.list_images(&dir).unwrap();
+
+        let nested = images
+            .iter()
+            .find(|image| image.name == os(b"sub\xff/\xfe.jpg"))
+            .unwrap();
+        assert_eq!(nested.path, dir.join(os(b"sub\xff")).join(os(b"\xfe.jpg")));
+        assert!(nested.path.is_file());
+
+        let directories = repository.list_directories(&dir).unwrap();
+        assert_eq!(directories, [dir.join(os(b"sub\xff"))]);
+    }
 }
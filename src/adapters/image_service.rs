@@ -1,18 +1,56 @@
-use std::path::Path;
+use std::{
+    fs::File,
+    io::{BufReader, Read},
+    path::Path,
+};
 
-use anyhow::{Context, Result};
+use anyhow::{Context, Result, bail};
 use image::{DynamicImage, ImageReader};
 
-use crate::ports::image_service_port::ImageServicePort;
+use crate::{models::image_format::ImageFormat, ports::image_service_port::ImageServicePort};
 
 #[derive(Clone)]
 pub struct ImageService;
 
+impl ImageService {
+    // The format is detected from the content, the extension may be wrong
+    fn reader(&self, path: &Path) -> Result<ImageReader<BufReader<File>>> {
+        if let Some(format) = self.format(path)
+            && !format.is_decodable()
+        {
+            bail!(
+                "walrust was built without {} support, see the cargo features",
+                format
+            );
+        }
+
+        ImageReader::open(path)
+            .and_then(|reader| reader.with_guessed_format())
+            .with_context(|| format!("Failed to open and read image: {:?}", path))
+    }
+}
+
 impl ImageServicePort for ImageService {
     fn decode(&self, path: &Path) -> Result<DynamicImage> {
-        ImageReader::open(path)
-            .with_context(|| format!("Failed to open and read image: {:?}", path))?
+        self.reader(path)?
             .decode()
             .with_context(|| format!("Failed to decode image: {:?}", path))
     }
+
+    // Only the first bytes are read
+    fn format(&self, path: &Path) -> Option<ImageFormat> {
+        let mut bytes = [0; 16];
+        let length = File::open(path)
+            .and_then(|mut file| file.read(&mut bytes))
+            .ok()?;
+
+        ImageFormat::from_magic(&bytes[..length])
+    }
+
+    // The header holds the dimensions, reading it is much faster than decoding
+    fn dimensions(&self, path: &Path) -> Result<(u32, u32)> {
+        self.reader(path)?
+            .into_dimensions()
+            .with_context(|| format!("Failed to read the header of image: {:?}", path))
+    }
 }
@@ -1,11 +1,34 @@
+pub mod all_displays_monitor_provider;
+pub mod config_file_repository;
+pub mod gnome_slideshow_file;
+pub mod gsettings;
 pub mod hyprctl;
 pub mod image_disk_repository;
 pub mod image_service;
 pub mod monitor_provider;
+pub mod plasma;
+pub mod png_cache;
+pub mod schedule_file_repository;
+pub mod state_file_repository;
+pub mod swaymsg;
+pub mod swww;
+mod utils;
 pub mod wallpaper_service;
+pub mod x11;
 
+pub use all_displays_monitor_provider::*;
+pub use config_file_repository::*;
+pub use gnome_slideshow_file::*;
+pub use gsettings::*;
 pub use hyprctl::*;
 pub use image_disk_repository::*;
 pub use image_service::*;
 pub use monitor_provider::*;
+pub use plasma::*;
+pub use png_cache::*;
+pub use schedule_file_repository::*;
+pub use state_file_repository::*;
+pub use swaymsg::*;
+pub use swww::*;
 pub use wallpaper_service::*;
+pub use x11::*;
@@ -1,24 +1,56 @@
 use crate::{
-    adapters::hyprctl::HyprctlMonitorProvider, models::desktop::Desktop, ports::MonitorProviderPort,
+    adapters::{
+        AllDisplaysMonitorProvider, hyprctl::HyprctlMonitorProvider, plasma::PlasmaMonitorProvider,
+        swaymsg::SwaymsgMonitorProvider, swww::SwwwMonitorProvider, x11::XrandrMonitorProvider,
+    },
+    models::{backend::Backend, desktop::Desktop, monitor::Monitor},
+    ports::MonitorProviderPort,
 };
 
 pub enum MonitorProvider {
     Hyprctl(HyprctlMonitorProvider),
+    Swaymsg(SwaymsgMonitorProvider),
+    Swww(SwwwMonitorProvider),
+    Plasma(PlasmaMonitorProvider),
+    Xrandr(XrandrMonitorProvider),
+    AllDisplays(AllDisplaysMonitorProvider),
 }
 
 impl MonitorProvider {
-    pub fn from(desktop: &Desktop) -> Option<Self> {
-        match desktop {
-            Desktop::Hyprland => Some(MonitorProvider::Hyprctl(HyprctlMonitorProvider)),
+    // The desktop knows its monitors best, but some backends can't
+    // set a wallpaper per monitor (feh), and swww can list the monitors
+    // of desktops that can't do it by themselves.
+    pub fn from(desktop: &Desktop, backend: &Backend) -> Option<Self> {
+        match (desktop, backend) {
+            (_, Backend::Feh) => Some(MonitorProvider::AllDisplays(AllDisplaysMonitorProvider)),
+            (Desktop::Hyprland, _) => Some(MonitorProvider::Hyprctl(HyprctlMonitorProvider)),
+            (Desktop::Sway, _) => Some(MonitorProvider::Swaymsg(SwaymsgMonitorProvider)),
+            (Desktop::Gnome | Desktop::Cinnamon | Desktop::Mate, _) => {
+                Some(MonitorProvider::AllDisplays(AllDisplaysMonitorProvider))
+            }
+            (Desktop::Kde, _) => Some(MonitorProvider::Plasma(PlasmaMonitorProvider)),
+            (desktop, _) if desktop.is_x11() => {
+                Some(MonitorProvider::Xrandr(XrandrMonitorProvider))
+            }
+            (_, Backend::Swww) => Some(MonitorProvider::Swww(SwwwMonitorProvider)),
             _ => None,
         }
     }
 }
 
 impl MonitorProviderPort for MonitorProvider {
-    fn get_monitors(&self) -> anyhow::Result<Vec<String>> {
-        match self {
+    fn get_monitors(&self) -> anyhow::Result<Vec<Monitor>> {
+        let mut monitors = match self {
             Self::Hyprctl(provider) => provider.get_monitors(),
-        }
+            Self::Swaymsg(provider) => provider.get_monitors(),
+            Self::Swww(provider) => provider.get_monitors(),
+            Self::Plasma(provider) => provider.get_monitors(),
+            Self::Xrandr(provider) => provider.get_monitors(),
+            Self::AllDisplays(provider) => provider.get_monitors(),
+        }?;
+
+        Monitor::mark_shared_descriptions(&mut monitors);
+
+        Ok(monitors)
     }
 }
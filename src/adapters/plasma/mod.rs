@@ -0,0 +1,6 @@
+mod plasma_monitor_provider;
+mod plasma_wallpaper_service;
+mod utils;
+
+pub use plasma_monitor_provider::*;
+pub use plasma_wallpaper_service::*;
@@ -0,0 +1,98 @@
+use crate::{
+    adapters::ALL_DISPLAYS,
+    models::monitor::{Monitor, MonitorGeometry},
+    ports::MonitorProviderPort,
+};
+
+use anyhow::{Result, anyhow};
+
+use super::utils;
+
+pub const SCREEN_PREFIX: &str = "Screen ";
+
+/*
+ * Plasma addresses its desktops by screen index, but the scripting API doesn't
+ * know connector names. The script prints the geometry of each screen and
+ * kscreen-doctor names the output at the same position.
+ * Screens it can't name are listed as "Screen <index>".
+ */
+
+pub struct PlasmaMonitorProvider;
+
+impl PlasmaMonitorProvider {
+    pub fn screen_index(monitor_name: &str) -> Result<u32> {
+        if let Some(index) = monitor_name
+            .strip_prefix(SCREEN_PREFIX)
+            .and_then(|index| index.parse().ok())
+        {
+            return Ok(index);
+        }
+
+        Self::screens()?
+            .into_iter()
+            .find(|(_, monitor)| monitor.name == monitor_name)
+            .map(|(index, _)| index)
+            .ok_or(anyhow!("Invalid Plasma screen: {}", monitor_name))
+    }
+
+    fn screens() -> Result<Vec<(u32, Monitor)>> {
+        // Desktops that are not shown on any screen have a negative index
+        let script = "desktops()
+            .filter(d => d.screen >= 0)
+            .sort((a, b) => a.screen - b.screen)
+            .forEach(d => {
+                const g = screenGeometry(d.screen);
+                print([d.screen, g.x, g.y, g.width, g.height].join(' ') + '\\n');
+            });";
+
+        let output = utils::evaluate_script(script)?;
+        // Names are a nicety, the screens still work without kscreen-doctor
+        let connectors = utils::connector_names().unwrap_or_default();
+
+        Ok(output
+            .lines()
+            .filter_map(|line| {
+                let columns: Vec<i64> = line
+                    .split_whitespace()
+                    .map(|column| column.parse().ok())
+                    .collect::<Option<_>>()?;
+                let [index, x, y, width, height] = columns[..] else {
+                    return None;
+                };
+                let (x, y) = (x as i32, y as i32);
+
+                let (name, scale) = match connectors.get(&(x, y)) {
+                    Some((name, scale)) => (name.clone(), *scale),
+                    None => (format!("{}{}", SCREEN_PREFIX, index), 1.0),
+                };
+
+                // The script reports logical sizes
+                let geometry = MonitorGeometry {
+                    width: (width as f64 * scale).round() as u32,
+                    height: (height as f64 * scale).round() as u32,
+                    x,
+                    y,
+                    scale,
+                    transform: 0,
+                };
+
+                Some((index as u32, Monitor::new(name).with_geometry(geometry)))
+            })
+            .collect())
+    }
+}
+
+impl MonitorProviderPort for PlasmaMonitorProvider {
+    // Without the Plasma shell only plasma-apply-wallpaperimage works,
+    // and it changes every screen at once.
+    fn get_monitors(&self) -> Result<Vec<Monitor>> {
+        if !utils::is_shell_running() {
+            return Ok(vec![Monitor::new(ALL_DISPLAYS)]);
+        }
+
+        Ok(Self::screens()?
+            .into_iter()
+            .map(|(_, monitor)| monitor)
+            .collect())
+    }
+}
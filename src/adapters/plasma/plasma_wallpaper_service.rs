@@ -0,0 +1,101 @@
+use anyhow::{Context, Result, ensure};
+use std::{path::Path, process::Command};
+
+use crate::{
+    adapters::{ALL_DISPLAYS, utils::file_uri},
+    models::{fit_mode::FitMode, image_format::ImageFormat, monitor::Monitor},
+    ports::wallpaper_service_port::WallpaperServicePort,
+};
+
+use super::{PlasmaMonitorProvider, utils};
+
+#[derive(Default)]
+pub struct PlasmaWallpaperService {
+    fit_mode: FitMode,
+}
+
+impl PlasmaWallpaperService {
+    pub fn new(fit_mode: FitMode) -> Self {
+        PlasmaWallpaperService { fit_mode }
+    }
+
+    // Values of the FillMode option of org.kde.image
+    fn fill_mode(&self) -> u8 {
+        match self.fit_mode {
+            FitMode::Stretch => 0,
+            FitMode::Fit => 1,
+            FitMode::Fill => 2,
+            FitMode::Tile => 3,
+            FitMode::Center => 6,
+        }
+    }
+
+    fn set_with_script(&self, screen: u32, path: &Path) -> Result<()> {
+        // The URI is percent-encoded, so it is safe to put it inside a JS string
+        let script = format!(
+            "desktops()
+                .filter(d => d.screen == {screen})
+                .forEach(d => {{
+                    d.wallpaperPlugin = 'org.kde.image';
+                    d.currentConfigGroup = ['Wallpaper', 'org.kde.image', 'General'];
+                    d.writeConfig('Image', '{uri}');
+                    d.writeConfig('FillMode', {fill_mode});
+                }});",
+            uri = file_uri(path),
+            fill_mode = self.fill_mode(),
+        );
+
+        let _ = utils::evaluate_script(&script)?;
+
+        Ok(())
+    }
+
+    // plasma-apply-wallpaperimage sets the wallpaper on every screen
+    fn set_with_apply_wallpaperimage(&self, path: &Path) -> Result<()> {
+        let output = Command::new("plasma-apply-wallpaperimage")
+            .arg(path)
+            .output()
+            .context("Failed to run plasma-apply-wallpaperimage command")?;
+
+        ensure!(
+            output.status.success(),
+            "plasma-apply-wallpaperimage command failed and returned: {}",
+            if output.stderr.is_empty() {
+                String::from_utf8_lossy(&output.stdout)
+            } else {
+                String::from_utf8_lossy(&output.stderr)
+            }
+            .trim()
+        );
+
+        Ok(())
+    }
+}
+
+impl WallpaperServicePort for PlasmaWallpaperService {
+    // Plasma loads images with the Qt image plugins, the other formats are converted to PNG
+    fn supported_formats(&self) -> &'static [ImageFormat] {
+        &[
+            ImageFormat::Png,
+            ImageFormat::Jpeg,
+            ImageFormat::Webp,
+            ImageFormat::Gif,
+            ImageFormat::Bmp,
+            ImageFormat::Tiff,
+        ]
+    }
+
+    fn set_wallpaper(&self, monitor: &Monitor, path: &Path) -> Result<()> {
+        // Only listed when the Plasma shell can't be reached
+        if monitor.name == ALL_DISPLAYS {
+            return self
+                .set_with_apply_wallpaperimage(path)
+                .context("Failed to change wallpaper");
+        }
+
+        let screen = PlasmaMonitorProvider::screen_index(&monitor.name)?;
+
+        self.set_with_script(screen, path)
+            .with_context(|| format!("Failed to change wallpaper of {}", monitor.name))
+    }
+}
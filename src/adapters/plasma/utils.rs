@@ -0,0 +1,97 @@
+use anyhow::{Context, Result, anyhow, ensure};
+use serde_json::Value;
+use std::{collections::HashMap, process::Command};
+
+// Asks the session bus whether the Plasma shell is running,
+// it isn't when dbus-send is missing or fails.
+pub fn is_shell_running() -> bool {
+    Command::new("dbus-send")
+        .args([
+            "--session",
+            "--print-reply",
+            "--dest=org.freedesktop.DBus",
+            "/org/freedesktop/DBus",
+            "org.freedesktop.DBus.NameHasOwner",
+            "string:org.kde.plasmashell",
+        ])
+        .output()
+        // The reply ends with "boolean true" or "boolean false"
+        .map(|output| {
+            output.status.success()
+                && String::from_utf8_lossy(&output.stdout).contains("boolean true")
+        })
+        .unwrap_or(false)
+}
+
+// Evaluates a Plasma shell script over DBus and returns what the script printed.
+// dbus-send is used instead of qdbus because qdbus is named differently
+// between distributions (qdbus, qdbus6, qdbus-qt6...).
+pub fn evaluate_script(script: &str) -> Result<String> {
+    let output = Command::new("dbus-send")
+        .args([
+            "--session",
+            "--print-reply",
+            "--dest=org.kde.plasmashell",
+            "/PlasmaShell",
+            "org.kde.PlasmaShell.evaluateScript",
+            &format!("string:{}", script),
+        ])
+        .output()
+        .context("Failed to run dbus-send command")?;
+
+    ensure!(
+        output.status.success(),
+        "Plasma shell script failed and returned: {}",
+        String::from_utf8_lossy(&output.stderr).trim()
+    );
+
+    // The reply looks like:
+    // method return time=... sender=... -> destination=... serial=...
+    //    string "printed output"
+    let stdout_utf8 = String::from_utf8_lossy(&output.stdout);
+    let start = stdout_utf8
+        .find("string \"")
+        .map(|index| index + "string \"".len())
+        .ok_or(anyhow!("Plasma shell returned an unexpected reply"))?;
+    let end = stdout_utf8
+        .rfind('"')
+        .filter(|end| *end >= start)
+        .ok_or(anyhow!("Plasma shell returned an unexpected reply"))?;
+
+    Ok(stdout_utf8[start..end].to_owned())
+}
+
+// Connector names by the position of the output, from `kscreen-doctor --json`
+pub fn connector_names() -> Result<HashMap<(i32, i32), (String, f64)>> {
+    let output = Command::new("kscreen-doctor")
+        .arg("--json")
+        .output()
+        .context("Failed to run kscreen-doctor command")?;
+
+    ensure!(
+        output.status.success(),
+        "kscreen-doctor command failed and returned: {}",
+        String::from_utf8_lossy(&output.stderr).trim()
+    );
+
+    let json: Value = serde_json::from_slice(&output.stdout)
+        .context("kscreen-doctor returned an unexpected reply")?;
+
+    Ok(json["outputs"]
+        .as_array()
+        .map(Vec::as_slice)
+        .unwrap_or_default()
+        .iter()
+        .filter(|output| output["enabled"].as_bool().unwrap_or_default())
+        .filter_map(|output| {
+            let position = (
+                output["pos"]["x"].as_i64()? as i32,
+                output["pos"]["y"].as_i64()? as i32,
+            );
+            let name = output["name"].as_str()?.to_owned();
+            let scale = output["scale"].as_f64().unwrap_or(1.0);
+
+            Some((position, (name, scale)))
+        })
+        .collect())
+}
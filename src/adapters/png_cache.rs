@@ -0,0 +1,178 @@
+use std::{
+    fs::{self, File},
+    path::{Path, PathBuf},
+    time::{SystemTime, UNIX_EPOCH},
+};
+
+use anyhow::{Context, Result};
+
+use crate::{
+    adapters::{ImageService, utils},
+    ports::ImageServicePort,
+};
+
+// Past this size the least recently used copies are removed
+const MAX_SIZE: u64 = 512 * 1024 * 1024;
+
+/*
+ * PngCache keeps PNG copies of the images whose format a backend can't display,
+ * e.g. AVIF or BMP on hyprpaper.
+ * A copy is named after the path, size and modification time of the image,
+ * so an image is converted again when it changes.
+ * The modification time of a copy is when it was last used.
+ */
+
+pub struct PngCache {
+    // Dependencies
+    image_service: ImageService,
+
+    // Data or states
+    dir: PathBuf,
+}
+
+impl PngCache {
+    pub fn new(dir: impl Into<PathBuf>) -> Self {
+        PngCache {
+            image_service: ImageService,
+            dir: dir.into(),
+        }
+    }
+
+    // $XDG_CACHE_HOME/walrust/png, or ~/.cache/walrust/png
+    pub fn default_path() -> Result<PathBuf> {
+        Ok(utils::cache_dir()?.join("png"))
+    }
+
+    // Returns the PNG copy of the image, converting it the first time
+    pub fn convert(&self, path: &Path) -> Result<PathBuf> {
+        let metadata = fs::metadata(path).with_context(|| format!("Failed to read {:?}", path))?;
+
+        let modified = metadata
+            .modified()
+            .ok()
+            .and_then(|time| time.duration_since(UNIX_EPOCH).ok())
+            .unwrap_or_default();
+
+        let mut hash = Fnv1a::new();
+        hash.write(path.as_os_str().as_encoded_bytes());
+        hash.write(&metadata.len().to_le_bytes());
+        hash.write(&modified.as_secs().to_le_bytes());
+        hash.write(&modified.subsec_nanos().to_le_bytes());
+
+        let png_path = self.dir.join(format!("{:016x}.png", hash.finish()));
+        if png_path.exists() {
+            // Only for the order of pruning, so failing is harmless
+            let _ = File::options()
+                .write(true)
+                .open(&png_path)
+                .and_then(|file| file.set_modified(SystemTime::now()));
+            return Ok(png_path);
+        }
+
+        fs::create_dir_all(&self.dir)
+            .with_context(|| format!("Failed to create {:?}", self.dir))?;
+
+        // Written under another name first, so an interrupted conversion isn't used
+        let partial_path = png_path.with_extension("partial");
+        self.image_service
+            .decode(path)?
+            .save_with_format(&partial_path, image::ImageFormat::Png)
+            .with_context(|| format!("Failed to write {:?}", partial_path))?;
+        fs::rename(&partial_path, &png_path)
+            .with_context(|| format!("Failed to write {:?}", png_path))?;
+
+        // The copy is usable even when the cache can't be cleaned up
+        let _ = self.prune(MAX_SIZE);
+
+        Ok(png_path)
+    }
+
+    // Removes the least recently used files until the rest fit in `max_size`
+    fn prune(&self, max_size: u64) -> Result<()> {
+        let mut files: Vec<(SystemTime, u64, PathBuf)> = fs::read_dir(&self.dir)
+            .with_context(|| format!("Failed to read {:?}", self.dir))?
+            .filter_map(|entry| {
+                let entry = entry.ok()?;
+                let metadata = entry
+                    .metadata()
+                    .ok()
+                    .filter(|metadata| metadata.is_file())?;
+                Some((metadata.modified().ok()?, metadata.len(), entry.path()))
+            })
+            .collect();
+        files.sort();
+
+        let mut size: u64 = files.iter().map(|(_, len, _)| len).sum();
+        for (_, len, path) in files {
+            if size <= max_size {
+                break;
+            }
+            fs::remove_file(&path).with_context(|| format!("Failed to remove {:?}", path))?;
+            size -= len;
+        }
+
+        Ok(())
+    }
+}
+
+// FNV-1a, unlike DefaultHasher the same in every Rust release
+struct Fnv1a(u64);
+
+impl Fnv1a {
+    fn new() -> Self {
+        Fnv1a(0xcbf29ce484222325)
+    }
+
+    fn write(&mut self, bytes: &[u8]) {
+        for byte in bytes {
+            self.0 ^= *byte as u64;
+            self.0 = self.0.wrapping_mul(0x100000001b3);
+        }
+    }
+
+    fn finish(&self) -> u64 {
+        self.0
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use super::*;
+    use crate::test_utils::TempDir;
+
+    #[test]
+    fn fnv1a_matches_the_reference() {
+        let mut hash = Fnv1a::new();
+        assert_eq!(hash.finish(), 0xcbf29ce484222325);
+
+        hash.write(b"foobar");
+        assert_eq!(hash.finish(), 0x85944171f73967e8);
+    }
+
+    #[test]
+    fn prune_removes_the_least_recently_used_files() {
+        let dir = TempDir::new("png-cache");
+        let now = SystemTime::now();
+
+        for (name, age) in [("old.png", 30), ("new.png", 10), ("used.png", 0)] {
+            let path = dir.join(name);
+            fs::write(&path, [0; 100]).unwrap();
+            File::options()
+                .write(true)
+                .open(&path)
+                .and_then(|file| file.set_modified(now - Duration::from_secs(age)))
+                .unwrap();
+        }
+
+        let cache = PngCache::new(&*dir);
+        cache.prune(250).unwrap();
+        assert!(!dir.join("old.png").exists());
+        assert!(dir.join("new.png").exists());
+
+        cache.prune(100).unwrap();
+        assert!(!dir.join("new.png").exists());
+        assert!(dir.join("used.png").exists());
+    }
+}
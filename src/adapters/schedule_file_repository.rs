@@ -0,0 +1,61 @@
+use anyhow::{Context, Result};
+use std::{
+    fs,
+    path::{Path, PathBuf},
+};
+
+use crate::{
+    adapters::{GnomeSlideshowFile, utils},
+    models::{schedule::Schedule, timetable::Timetable},
+    ports::ScheduleRepository,
+};
+
+pub struct ScheduleFileRepository {
+    path: PathBuf,
+}
+
+impl ScheduleFileRepository {
+    pub fn new(path: impl Into<PathBuf>) -> Self {
+        ScheduleFileRepository { path: path.into() }
+    }
+
+    // $XDG_CONFIG_HOME/walrust/schedule.toml, or ~/.config/walrust/schedule.toml
+    pub fn default_path() -> Result<PathBuf> {
+        Ok(utils::config_dir()?.join("schedule.toml"))
+    }
+
+    // Paths may start with ~ or be relative to the schedule file
+    fn resolve_path(&self, path: &Path) -> PathBuf {
+        let path = utils::expand_home(path);
+
+        match self.path.parent() {
+            Some(parent) => parent.join(path),
+            None => path,
+        }
+    }
+}
+
+impl ScheduleRepository for ScheduleFileRepository {
+    // A GNOME slideshow XML file is accepted too
+    fn load(&self) -> Result<Timetable> {
+        if GnomeSlideshowFile::is_slideshow(&self.path) {
+            let slideshow = GnomeSlideshowFile::new(&self.path).load()?;
+            return Ok(Timetable::Slideshow(slideshow));
+        }
+
+        let content = fs::read_to_string(&self.path)
+            .with_context(|| format!("Failed to read {:?}", self.path))?;
+
+        let mut schedule: Schedule =
+            toml::from_str(&content).with_context(|| format!("Failed to parse {:?}", self.path))?;
+        schedule
+            .validate()
+            .with_context(|| format!("Invalid schedule {:?}", self.path))?;
+
+        for entry in &mut schedule.entries {
+            entry.path = self.resolve_path(&entry.path);
+        }
+
+        Ok(Timetable::Schedule(schedule))
+    }
+}
@@ -0,0 +1,49 @@
+use anyhow::{Context, Result, anyhow};
+use std::{env, fs, path::PathBuf};
+
+use crate::{models::wallpaper_state::WallpaperState, ports::StateRepository};
+
+pub struct StateFileRepository {
+    path: PathBuf,
+}
+
+impl StateFileRepository {
+    pub fn new(path: impl Into<PathBuf>) -> Self {
+        StateFileRepository { path: path.into() }
+    }
+
+    // $XDG_STATE_HOME/walrust/state.json, or ~/.local/state/walrust/state.json
+    pub fn default_path() -> Result<PathBuf> {
+        let state_dir = env::var_os("XDG_STATE_HOME")
+            .map(PathBuf::from)
+            .or_else(|| {
+                env::var_os("HOME").map(|home| PathBuf::from(home).join(".local").join("state"))
+            })
+            .ok_or(anyhow!("Neither XDG_STATE_HOME nor HOME is set"))?;
+
+        Ok(state_dir.join("walrust").join("state.json"))
+    }
+}
+
+impl StateRepository for StateFileRepository {
+    fn load(&self) -> Result<WallpaperState> {
+        if !self.path.exists() {
+            return Ok(WallpaperState::default());
+        }
+
+        let content = fs::read_to_string(&self.path)
+            .with_context(|| format!("Failed to read {:?}", self.path))?;
+
+        serde_json::from_str(&content).with_context(|| format!("Failed to parse {:?}", self.path))
+    }
+
+    fn save(&self, state: &WallpaperState) -> Result<()> {
+        if let Some(parent) = self.path.parent() {
+            fs::create_dir_all(parent).with_context(|| format!("Failed to create {:?}", parent))?;
+        }
+
+        let content = serde_json::to_string_pretty(state)?;
+
+        fs::write(&self.path, content).with_context(|| format!("Failed to write {:?}", self.path))
+    }
+}
@@ -0,0 +1,6 @@
+mod swaymsg_monitor_provider;
+mod swaymsg_wallpaper_service;
+mod utils;
+
+pub use swaymsg_monitor_provider::*;
+pub use swaymsg_wallpaper_service::*;
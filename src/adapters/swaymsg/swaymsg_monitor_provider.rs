@@ -0,0 +1,75 @@
+use crate::{
+    models::monitor::{Monitor, MonitorGeometry},
+    ports::MonitorProviderPort,
+};
+
+use anyhow::{Context, Result, anyhow};
+use serde_json::Value;
+
+pub struct SwaymsgMonitorProvider;
+
+impl SwaymsgMonitorProvider {
+    fn parse_monitor(output: &Value) -> Option<Monitor> {
+        let name = output["name"].as_str()?;
+
+        // Hyprland style description, e.g. "Dell Inc. DELL U2720Q ABCD123"
+        let description = ["make", "model", "serial"]
+            .iter()
+            .filter_map(|key| output[key].as_str())
+            .filter(|value| !value.is_empty() && *value != "Unknown")
+            .collect::<Vec<&str>>()
+            .join(" ");
+
+        let geometry = MonitorGeometry {
+            width: output["current_mode"]["width"].as_u64().unwrap_or_default() as u32,
+            height: output["current_mode"]["height"]
+                .as_u64()
+                .unwrap_or_default() as u32,
+            x: output["rect"]["x"].as_i64().unwrap_or_default() as i32,
+            y: output["rect"]["y"].as_i64().unwrap_or_default() as i32,
+            scale: output["scale"].as_f64().unwrap_or(1.0),
+            transform: Self::transform(output["transform"].as_str().unwrap_or_default()),
+        };
+
+        Some(
+            Monitor::new(name)
+                .with_description(description)
+                .with_geometry(geometry)
+                .with_focused(output["focused"].as_bool().unwrap_or_default()),
+        )
+    }
+
+    fn transform(transform: &str) -> u8 {
+        match transform {
+            "90" => 1,
+            "180" => 2,
+            "270" => 3,
+            "flipped" => 4,
+            "flipped-90" => 5,
+            "flipped-180" => 6,
+            "flipped-270" => 7,
+            _ => 0,
+        }
+    }
+}
+
+impl MonitorProviderPort for SwaymsgMonitorProvider {
+    fn get_monitors(&self) -> Result<Vec<Monitor>> {
+        let output = super::utils::swaymsg(&["-t", "get_outputs", "-r"])?;
+
+        let stdout_utf8 = String::from_utf8_lossy(&output.stdout);
+        let outputs: Value = serde_json::from_str(&stdout_utf8)
+            .context("Failed to convert swaymsg output to JSON")?;
+
+        if let Some(outputs_array) = outputs.as_array() {
+            // Disabled outputs are listed too, but they can't show a wallpaper
+            Ok(outputs_array
+                .iter()
+                .filter(|output| output["active"].as_bool().unwrap_or(true))
+                .filter_map(Self::parse_monitor)
+                .collect())
+        } else {
+            Err(anyhow!("swaymsg command returned nothing or not an array"))
+        }
+    }
+}
@@ -0,0 +1,63 @@
+use anyhow::{Context, Result, ensure};
+use std::path::Path;
+
+use crate::{
+    models::{fit_mode::FitMode, image_format::ImageFormat, monitor::Monitor},
+    ports::wallpaper_service_port::WallpaperServicePort,
+};
+
+use super::utils;
+
+#[derive(Default)]
+pub struct SwaymsgWallpaperService {
+    fit_mode: FitMode,
+}
+
+impl SwaymsgWallpaperService {
+    pub fn new(fit_mode: FitMode) -> Self {
+        SwaymsgWallpaperService { fit_mode }
+    }
+
+    fn mode(&self) -> &'static str {
+        match self.fit_mode {
+            FitMode::Fill => "fill",
+            FitMode::Fit => "fit",
+            FitMode::Stretch => "stretch",
+            FitMode::Center => "center",
+            FitMode::Tile => "tile",
+        }
+    }
+}
+
+impl WallpaperServicePort for SwaymsgWallpaperService {
+    // swaybg loads images with gdk-pixbuf, the other formats are converted to PNG
+    fn supported_formats(&self) -> &'static [ImageFormat] {
+        &[
+            ImageFormat::Png,
+            ImageFormat::Jpeg,
+            ImageFormat::Gif,
+            ImageFormat::Bmp,
+            ImageFormat::Tiff,
+        ]
+    }
+
+    fn set_wallpaper(&self, monitor: &Monitor, path: &Path) -> Result<()> {
+        ensure!(
+            path.to_str().is_some(),
+            "swaymsg can't set a wallpaper with a non UTF-8 path: {:?}",
+            path
+        );
+
+        let path_string = path.display().to_string();
+        let command = format!(
+            "output {} bg {} {}",
+            utils::quote(&monitor.name),
+            utils::quote(&path_string),
+            self.mode()
+        );
+
+        let _ = utils::swaymsg(&[&command]).context("Failed to change wallpaper")?;
+
+        Ok(())
+    }
+}
@@ -0,0 +1,30 @@
+use anyhow::{Context, Result, ensure};
+use std::process::{Command, Output};
+
+// swaymsg exits with a non-zero status when the command fails,
+// but the error of a failed sway command (e.g. unknown output) is printed to stdout.
+pub fn swaymsg(args: &[&str]) -> Result<Output> {
+    let output = Command::new("swaymsg")
+        .args(args)
+        .output()
+        .context("Failed to run swaymsg command")?;
+
+    ensure!(
+        output.status.success(),
+        "swaymsg command failed and returned: {}",
+        if output.stderr.is_empty() {
+            String::from_utf8_lossy(&output.stdout)
+        } else {
+            String::from_utf8_lossy(&output.stderr)
+        }
+        .trim()
+    );
+
+    Ok(output)
+}
+
+// Sway parses the command as a string, so arguments containing
+// spaces or quotes must be quoted.
+pub fn quote(arg: &str) -> String {
+    format!("\"{}\"", arg.replace('\\', "\\\\").replace('"', "\\\""))
+}
@@ -0,0 +1,8 @@
+mod swww_monitor_provider;
+mod swww_transition;
+mod swww_wallpaper_service;
+mod utils;
+
+pub use swww_monitor_provider::*;
+pub use swww_transition::*;
+pub use swww_wallpaper_service::*;
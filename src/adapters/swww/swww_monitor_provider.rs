@@ -0,0 +1,65 @@
+use crate::{
+    models::monitor::{Monitor, MonitorGeometry},
+    ports::MonitorProviderPort,
+};
+
+use anyhow::{Result, ensure};
+
+pub struct SwwwMonitorProvider;
+
+impl SwwwMonitorProvider {
+    // "DP-1: 2560x1440, scale: 1.25, currently displaying: ..."
+    fn parse_monitor(line: &str) -> Option<Monitor> {
+        let (name, info) = line.split_once(':')?;
+        let name = name.trim();
+        if name.is_empty() {
+            return None;
+        }
+
+        let mut fields = info.split(',').map(|field| field.trim());
+        let size = fields
+            .next()
+            .and_then(|size| size.split_once('x'))
+            .and_then(|(width, height)| Some((width.parse().ok()?, height.parse().ok()?)));
+        let scale = fields
+            .find_map(|field| field.strip_prefix("scale:"))
+            .and_then(|scale| scale.trim().parse().ok())
+            .unwrap_or(1.0);
+
+        let monitor = Monitor::new(name);
+
+        Some(match size {
+            Some((width, height)) => monitor.with_geometry(MonitorGeometry {
+                width,
+                height,
+                x: 0,
+                y: 0,
+                scale,
+                transform: 0,
+            }),
+            None => monitor,
+        })
+    }
+}
+
+impl MonitorProviderPort for SwwwMonitorProvider {
+    // `swww query` prints one output per line, e.g.
+    // "DP-1: 2560x1440, scale: 1, currently displaying: image: /path/to/image.png"
+    // Newer versions prefix the line with ": ".
+    fn get_monitors(&self) -> Result<Vec<Monitor>> {
+        let output = super::utils::swww(&["query"])?;
+
+        ensure!(
+            output.status.success(),
+            "swww command failed and returned: {}",
+            String::from_utf8_lossy(&output.stderr).trim()
+        );
+
+        let stdout_utf8 = String::from_utf8_lossy(&output.stdout);
+        Ok(stdout_utf8
+            .lines()
+            .map(|line| line.trim_start_matches(':').trim())
+            .filter_map(Self::parse_monitor)
+            .collect())
+    }
+}
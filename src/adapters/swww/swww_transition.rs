@@ -0,0 +1,150 @@
+use std::{env, str::FromStr};
+
+use anyhow::{Context, Result, anyhow, ensure};
+
+// See `swww img --help` for the description of each transition type
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SwwwTransitionType {
+    None,
+    Simple,
+    Fade,
+    Left,
+    Right,
+    Top,
+    Bottom,
+    Wipe,
+    Wave,
+    Grow,
+    Center,
+    Any,
+    Outer,
+    Random,
+}
+
+impl SwwwTransitionType {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::None => "none",
+            Self::Simple => "simple",
+            Self::Fade => "fade",
+            Self::Left => "left",
+            Self::Right => "right",
+            Self::Top => "top",
+            Self::Bottom => "bottom",
+            Self::Wipe => "wipe",
+            Self::Wave => "wave",
+            Self::Grow => "grow",
+            Self::Center => "center",
+            Self::Any => "any",
+            Self::Outer => "outer",
+            Self::Random => "random",
+        }
+    }
+}
+
+impl FromStr for SwwwTransitionType {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        match s.to_lowercase().as_str() {
+            "none" => Ok(Self::None),
+            "simple" => Ok(Self::Simple),
+            "fade" => Ok(Self::Fade),
+            "left" => Ok(Self::Left),
+            "right" => Ok(Self::Right),
+            "top" => Ok(Self::Top),
+            "bottom" => Ok(Self::Bottom),
+            "wipe" => Ok(Self::Wipe),
+            "wave" => Ok(Self::Wave),
+            "grow" => Ok(Self::Grow),
+            "center" => Ok(Self::Center),
+            "any" => Ok(Self::Any),
+            "outer" => Ok(Self::Outer),
+            "random" => Ok(Self::Random),
+            _ => Err(anyhow!("Unknown swww transition type: {}", s)),
+        }
+    }
+}
+
+/*
+ * Every option is optional, unset options are not passed to swww
+ * so swww falls back to its own default (or its SWWW_TRANSITION_* env vars).
+ */
+
+#[derive(Debug, Clone, Default)]
+pub struct SwwwTransition {
+    pub transition_type: Option<SwwwTransitionType>,
+    pub duration: Option<f32>, // In seconds
+    pub fps: Option<u32>,
+    pub position: Option<String>, // e.g. "center", "top-left" or "0.5,0.5"
+}
+
+impl SwwwTransition {
+    pub fn from_env() -> Result<Self> {
+        let transition_type = Self::parse_env::<SwwwTransitionType>("WALRUST_TRANSITION_TYPE")?;
+        let duration = Self::parse_env::<f32>("WALRUST_TRANSITION_DURATION")?;
+        let fps = Self::parse_env::<u32>("WALRUST_TRANSITION_FPS")?;
+        let position = env::var("WALRUST_TRANSITION_POS").ok();
+
+        // swww would reject these on every change, so they are reported once here
+        if let Some(duration) = duration {
+            ensure!(
+                duration.is_finite() && duration >= 0.0,
+                "WALRUST_TRANSITION_DURATION must be a number of seconds, not {}",
+                duration
+            );
+        }
+        ensure!(
+            fps != Some(0),
+            "WALRUST_TRANSITION_FPS must be greater than zero"
+        );
+
+        Ok(SwwwTransition {
+            transition_type,
+            duration,
+            fps,
+            position,
+        })
+    }
+
+    pub fn args(&self) -> Vec<String> {
+        let mut args = Vec::new();
+
+        if let Some(transition_type) = self.transition_type {
+            args.push("--transition-type".to_string());
+            args.push(transition_type.as_str().to_string());
+        }
+
+        if let Some(duration) = self.duration {
+            args.push("--transition-duration".to_string());
+            args.push(duration.to_string());
+        }
+
+        if let Some(fps) = self.fps {
+            args.push("--transition-fps".to_string());
+            args.push(fps.to_string());
+        }
+
+        if let Some(position) = &self.position {
+            args.push("--transition-pos".to_string());
+            args.push(position.clone());
+        }
+
+        args
+    }
+
+    fn parse_env<T>(env_var: &str) -> Result<Option<T>>
+    where
+        T: FromStr,
+        T::Err: Into<anyhow::Error>,
+    {
+        match env::var(env_var) {
+            Ok(value) => value
+                .parse::<T>()
+                .map(Some)
+                .map_err(Into::<anyhow::Error>::into)
+                .with_context(|| format!("Invalid value for {}: {}", env_var, value)),
+            Err(_) => Ok(None),
+        }
+    }
+}
@@ -0,0 +1,55 @@
+use anyhow::{Context, Result, ensure};
+use std::{ffi::OsString, path::Path};
+
+use crate::{
+    models::{image_format::ImageFormat, monitor::Monitor},
+    ports::wallpaper_service_port::WallpaperServicePort,
+};
+
+use super::{SwwwTransition, utils};
+
+#[derive(Default)]
+pub struct SwwwWallpaperService {
+    transition: SwwwTransition,
+}
+
+impl SwwwWallpaperService {
+    pub fn new(transition: SwwwTransition) -> Self {
+        SwwwWallpaperService { transition }
+    }
+}
+
+impl WallpaperServicePort for SwwwWallpaperService {
+    // Formats swww can display, the others are converted to PNG
+    fn supported_formats(&self) -> &'static [ImageFormat] {
+        &[
+            ImageFormat::Png,
+            ImageFormat::Jpeg,
+            ImageFormat::Webp,
+            ImageFormat::Gif,
+            ImageFormat::Bmp,
+            ImageFormat::Tiff,
+            ImageFormat::Qoi,
+        ]
+    }
+
+    fn set_wallpaper(&self, monitor: &Monitor, path: &Path) -> Result<()> {
+        let mut args: Vec<OsString> = vec![
+            "img".into(),
+            path.as_os_str().to_owned(),
+            "--outputs".into(),
+            monitor.name.as_str().into(),
+        ];
+        args.extend(self.transition.args().into_iter().map(OsString::from));
+
+        let output = utils::swww(&args).context("Failed to change wallpaper")?;
+
+        ensure!(
+            output.status.success(),
+            "swww command failed and returned: {}",
+            String::from_utf8_lossy(&output.stderr).trim()
+        );
+
+        Ok(())
+    }
+}
@@ -0,0 +1,13 @@
+use anyhow::{Context, Result};
+use std::{
+    ffi::OsStr,
+    process::{Command, Output},
+};
+
+// Unlike hyprctl, swww sends its error output to stderr.
+pub fn swww<S: AsRef<OsStr>>(args: &[S]) -> Result<Output> {
+    Command::new("swww")
+        .args(args)
+        .output()
+        .context("Failed to run swww command")
+}
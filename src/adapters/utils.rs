@@ -0,0 +1,53 @@
+use anyhow::{Result, anyhow};
+use std::{
+    env,
+    os::unix::ffi::OsStrExt,
+    path::{Path, PathBuf},
+};
+
+// Converts a path to a `file://` URI, percent-encoding every byte
+// that is not unreserved so non UTF-8 paths are kept intact.
+pub fn file_uri(path: &Path) -> String {
+    let mut uri = String::from("file://");
+
+    for &byte in path.as_os_str().as_bytes() {
+        if byte.is_ascii_alphanumeric() || b"/-_.~".contains(&byte) {
+            uri.push(byte as char);
+        } else {
+            uri.push_str(&format!("%{:02X}", byte));
+        }
+    }
+
+    uri
+}
+
+// Replaces a leading ~ with the home directory
+pub fn expand_home(path: &Path) -> PathBuf {
+    if let Ok(rest) = path.strip_prefix("~")
+        && let Some(home) = env::var_os("HOME")
+    {
+        return PathBuf::from(home).join(rest);
+    }
+
+    path.to_path_buf()
+}
+
+// $XDG_CACHE_HOME/walrust, or ~/.cache/walrust
+pub fn cache_dir() -> Result<PathBuf> {
+    let cache_dir = env::var_os("XDG_CACHE_HOME")
+        .map(PathBuf::from)
+        .or_else(|| env::var_os("HOME").map(|home| PathBuf::from(home).join(".cache")))
+        .ok_or(anyhow!("Neither XDG_CACHE_HOME nor HOME is set"))?;
+
+    Ok(cache_dir.join("walrust"))
+}
+
+// $XDG_CONFIG_HOME/walrust, or ~/.config/walrust
+pub fn config_dir() -> Result<PathBuf> {
+    let config_dir = env::var_os("XDG_CONFIG_HOME")
+        .map(PathBuf::from)
+        .or_else(|| env::var_os("HOME").map(|home| PathBuf::from(home).join(".config")))
+        .ok_or(anyhow!("Neither XDG_CONFIG_HOME nor HOME is set"))?;
+
+    Ok(config_dir.join("walrust"))
+}
@@ -1,24 +1,121 @@
+use std::path::{Path, PathBuf};
+
+use anyhow::Result;
+
 use crate::{
-    adapters::HyprctlWallpaperService, models::desktop::Desktop, ports::WallpaperServicePort,
+    adapters::{
+        FehWallpaperService, GsettingsSchema, GsettingsWallpaperService, HyprctlWallpaperService,
+        HyprpaperPersistence, ImageService, PlasmaWallpaperService, PngCache,
+        SwaymsgWallpaperService, SwwwTransition, SwwwWallpaperService, XwallpaperWallpaperService,
+    },
+    models::{backend::Backend, fit_mode::FitMode, image_format::ImageFormat, monitor::Monitor},
+    ports::{ImageServicePort, WallpaperServicePort},
 };
 
 pub enum WallpaperService {
     Hyprctl(HyprctlWallpaperService),
+    Swaymsg(SwaymsgWallpaperService),
+    Swww(SwwwWallpaperService),
+    Gsettings(GsettingsWallpaperService),
+    Plasma(PlasmaWallpaperService),
+    Xwallpaper(XwallpaperWallpaperService),
+    Feh(FehWallpaperService),
 }
 
 impl WallpaperService {
-    pub fn from(desktop: &Desktop) -> Option<Self> {
-        match desktop {
-            Desktop::Hyprland => Some(WallpaperService::Hyprctl(HyprctlWallpaperService)),
-            _ => None,
+    pub fn from(backend: &Backend) -> Option<Self> {
+        match backend {
+            Backend::Hyprpaper => {
+                Some(WallpaperService::Hyprctl(HyprctlWallpaperService::default()))
+            }
+            Backend::Swaymsg => Some(WallpaperService::Swaymsg(SwaymsgWallpaperService::default())),
+            Backend::Swww => Some(WallpaperService::Swww(SwwwWallpaperService::default())),
+            Backend::Gnome => Some(WallpaperService::Gsettings(GsettingsWallpaperService::new(
+                GsettingsSchema::Gnome,
+            ))),
+            Backend::Cinnamon => Some(WallpaperService::Gsettings(GsettingsWallpaperService::new(
+                GsettingsSchema::Cinnamon,
+            ))),
+            Backend::Mate => Some(WallpaperService::Gsettings(GsettingsWallpaperService::new(
+                GsettingsSchema::Mate,
+            ))),
+            Backend::Plasma => Some(WallpaperService::Plasma(PlasmaWallpaperService::default())),
+            Backend::Xwallpaper => Some(WallpaperService::Xwallpaper(
+                XwallpaperWallpaperService::default(),
+            )),
+            Backend::Feh => Some(WallpaperService::Feh(FehWallpaperService::default())),
+            Backend::Unknown => None,
+        }
+    }
+
+    // Transition options are only supported by swww, other backends ignore them.
+    pub fn with_transition(self, transition: SwwwTransition) -> Self {
+        match self {
+            Self::Swww(_) => Self::Swww(SwwwWallpaperService::new(transition)),
+            _ => self,
+        }
+    }
+
+    // Only hyprpaper reads its wallpapers from a config file on startup,
+    // other backends ignore it.
+    pub fn with_persistence(self, persistence: HyprpaperPersistence) -> Self {
+        match self {
+            Self::Hyprctl(service) => Self::Hyprctl(service.with_persistence(persistence)),
+            _ => self,
+        }
+    }
+
+    // Backends that can't scale the image ignore the fit mode.
+    pub fn with_fit_mode(self, fit_mode: FitMode) -> Self {
+        match self {
+            Self::Swaymsg(_) => Self::Swaymsg(SwaymsgWallpaperService::new(fit_mode)),
+            Self::Gsettings(service) => Self::Gsettings(service.with_fit_mode(fit_mode)),
+            Self::Plasma(_) => Self::Plasma(PlasmaWallpaperService::new(fit_mode)),
+            Self::Xwallpaper(_) => Self::Xwallpaper(XwallpaperWallpaperService::new(fit_mode)),
+            Self::Feh(_) => Self::Feh(FehWallpaperService::new(fit_mode)),
+            _ => self,
+        }
+    }
+
+    // An image the backend can't display is set from a PNG copy,
+    // unknown formats are left to the backend
+    fn convert_if_unsupported(&self, path: &Path) -> Result<Option<PathBuf>> {
+        match ImageService.format(path) {
+            Some(format) if !self.supported_formats().contains(&format) => {
+                PngCache::new(PngCache::default_path()?)
+                    .convert(path)
+                    .map(Some)
+            }
+            _ => Ok(None),
         }
     }
 }
 
 impl WallpaperServicePort for WallpaperService {
-    fn set_wallpaper(&self, monitor_name: &str, path: &std::path::Path) -> anyhow::Result<()> {
+    fn set_wallpaper(&self, monitor: &Monitor, path: &Path) -> Result<()> {
+        let converted = self.convert_if_unsupported(path)?;
+        let path = converted.as_deref().unwrap_or(path);
+
+        match self {
+            Self::Hyprctl(service) => service.set_wallpaper(monitor, path),
+            Self::Swaymsg(service) => service.set_wallpaper(monitor, path),
+            Self::Swww(service) => service.set_wallpaper(monitor, path),
+            Self::Gsettings(service) => service.set_wallpaper(monitor, path),
+            Self::Plasma(service) => service.set_wallpaper(monitor, path),
+            Self::Xwallpaper(service) => service.set_wallpaper(monitor, path),
+            Self::Feh(service) => service.set_wallpaper(monitor, path),
+        }
+    }
+
+    fn supported_formats(&self) -> &'static [ImageFormat] {
         match self {
-            Self::Hyprctl(service) => service.set_wallpaper(monitor_name, path),
+            Self::Hyprctl(service) => service.supported_formats(),
+            Self::Swaymsg(service) => service.supported_formats(),
+            Self::Swww(service) => service.supported_formats(),
+            Self::Gsettings(service) => service.supported_formats(),
+            Self::Plasma(service) => service.supported_formats(),
+            Self::Xwallpaper(service) => service.supported_formats(),
+            Self::Feh(service) => service.supported_formats(),
         }
     }
 }
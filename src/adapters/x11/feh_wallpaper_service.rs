@@ -0,0 +1,57 @@
+use anyhow::{Context, Result};
+use std::{ffi::OsStr, path::Path};
+
+use crate::{
+    models::{fit_mode::FitMode, image_format::ImageFormat, monitor::Monitor},
+    ports::wallpaper_service_port::WallpaperServicePort,
+};
+
+use super::utils;
+
+/*
+ * feh sets the wallpaper of the whole X screen,
+ * so the monitor name is ignored.
+ */
+
+#[derive(Default)]
+pub struct FehWallpaperService {
+    fit_mode: FitMode,
+}
+
+impl FehWallpaperService {
+    pub fn new(fit_mode: FitMode) -> Self {
+        FehWallpaperService { fit_mode }
+    }
+
+    fn fit_flag(&self) -> &'static str {
+        match self.fit_mode {
+            FitMode::Fill => "--bg-fill",
+            FitMode::Fit => "--bg-max",
+            FitMode::Stretch => "--bg-scale",
+            FitMode::Center => "--bg-center",
+            FitMode::Tile => "--bg-tile",
+        }
+    }
+}
+
+impl WallpaperServicePort for FehWallpaperService {
+    // feh loads images with imlib2, the other formats are converted to PNG
+    fn supported_formats(&self) -> &'static [ImageFormat] {
+        &[
+            ImageFormat::Png,
+            ImageFormat::Jpeg,
+            ImageFormat::Webp,
+            ImageFormat::Gif,
+            ImageFormat::Bmp,
+            ImageFormat::Tiff,
+        ]
+    }
+
+    fn set_wallpaper(&self, _monitor: &Monitor, path: &Path) -> Result<()> {
+        let args: [&OsStr; 2] = [self.fit_flag().as_ref(), path.as_os_str()];
+
+        let _ = utils::run("feh", &args).context("Failed to change wallpaper")?;
+
+        Ok(())
+    }
+}
@@ -0,0 +1,8 @@
+mod feh_wallpaper_service;
+mod utils;
+mod xrandr_monitor_provider;
+mod xwallpaper_wallpaper_service;
+
+pub use feh_wallpaper_service::*;
+pub use xrandr_monitor_provider::*;
+pub use xwallpaper_wallpaper_service::*;
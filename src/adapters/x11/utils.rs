@@ -0,0 +1,21 @@
+use anyhow::{Context, Result, ensure};
+use std::{
+    ffi::OsStr,
+    process::{Command, Output},
+};
+
+pub fn run<S: AsRef<OsStr>>(program: &str, args: &[S]) -> Result<Output> {
+    let output = Command::new(program)
+        .args(args)
+        .output()
+        .with_context(|| format!("Failed to run {} command", program))?;
+
+    ensure!(
+        output.status.success(),
+        "{} command failed and returned: {}",
+        program,
+        String::from_utf8_lossy(&output.stderr).trim()
+    );
+
+    Ok(output)
+}
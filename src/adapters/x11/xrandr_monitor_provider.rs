@@ -0,0 +1,59 @@
+use crate::{
+    models::monitor::{Monitor, MonitorGeometry},
+    ports::MonitorProviderPort,
+};
+
+use anyhow::Result;
+
+use super::utils;
+
+pub struct XrandrMonitorProvider;
+
+impl XrandrMonitorProvider {
+    // " 0: +*eDP-1 1920/344x1080/194+0+0  eDP-1"
+    fn parse_monitor(line: &str) -> Option<Monitor> {
+        let columns: Vec<&str> = line.split_whitespace().collect();
+        let name = columns.last()?;
+
+        // "1920/344x1080/194+0+0", the numbers after the slashes are millimeters
+        let geometry = columns.get(2).and_then(|geometry| {
+            let (size, position) = geometry.split_once('+')?;
+            let (x, y) = position.split_once('+')?;
+            let (width, height) = size.split_once('x')?;
+
+            Some(MonitorGeometry {
+                width: width.split('/').next()?.parse().ok()?,
+                height: height.split('/').next()?.parse().ok()?,
+                x: x.parse().ok()?,
+                y: y.parse().ok()?,
+                scale: 1.0,
+                transform: 0,
+            })
+        });
+
+        let monitor = Monitor::new(*name);
+
+        Some(match geometry {
+            Some(geometry) => monitor.with_geometry(geometry),
+            None => monitor,
+        })
+    }
+}
+
+impl MonitorProviderPort for XrandrMonitorProvider {
+    // `xrandr --listmonitors` output looks like:
+    // Monitors: 2
+    //  0: +*eDP-1 1920/344x1080/194+0+0  eDP-1
+    //  1: +HDMI-1 1920/521x1080/293+1920+0  HDMI-1
+    // The last column is the output name.
+    fn get_monitors(&self) -> Result<Vec<Monitor>> {
+        let output = utils::run("xrandr", &["--listmonitors"])?;
+
+        let stdout_utf8 = String::from_utf8_lossy(&output.stdout);
+        Ok(stdout_utf8
+            .lines()
+            .skip(1)
+            .filter_map(Self::parse_monitor)
+            .collect())
+    }
+}
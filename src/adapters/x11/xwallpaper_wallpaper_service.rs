@@ -0,0 +1,50 @@
+use anyhow::{Context, Result};
+use std::{ffi::OsStr, path::Path};
+
+use crate::{
+    models::{fit_mode::FitMode, image_format::ImageFormat, monitor::Monitor},
+    ports::wallpaper_service_port::WallpaperServicePort,
+};
+
+use super::utils;
+
+#[derive(Default)]
+pub struct XwallpaperWallpaperService {
+    fit_mode: FitMode,
+}
+
+impl XwallpaperWallpaperService {
+    pub fn new(fit_mode: FitMode) -> Self {
+        XwallpaperWallpaperService { fit_mode }
+    }
+
+    fn fit_flag(&self) -> &'static str {
+        match self.fit_mode {
+            FitMode::Fill => "--zoom",
+            FitMode::Fit => "--maximize",
+            FitMode::Stretch => "--stretch",
+            FitMode::Center => "--center",
+            FitMode::Tile => "--tile",
+        }
+    }
+}
+
+impl WallpaperServicePort for XwallpaperWallpaperService {
+    // Formats xwallpaper can display, the others are converted to PNG
+    fn supported_formats(&self) -> &'static [ImageFormat] {
+        &[ImageFormat::Png, ImageFormat::Jpeg]
+    }
+
+    fn set_wallpaper(&self, monitor: &Monitor, path: &Path) -> Result<()> {
+        let args: [&OsStr; 4] = [
+            "--output".as_ref(),
+            monitor.name.as_ref(),
+            self.fit_flag().as_ref(),
+            path.as_os_str(),
+        ];
+
+        let _ = utils::run("xwallpaper", &args).context("Failed to change wallpaper")?;
+
+        Ok(())
+    }
+}
@@ -0,0 +1,284 @@
+use std::{path::PathBuf, time::Duration};
+
+use anyhow::{Context, Result, ensure};
+use clap::{Args as ClapArgs, Parser, Subcommand, builder::RangedU64ValueParser};
+
+use crate::{
+    adapters::GnomeSlideshowFile,
+    cli::MonitorTarget,
+    daemon::protocol::DaemonRequest,
+    models::wallpaper_state::{DEFAULT_HISTORY, HISTORY_CAPACITY},
+};
+
+#[derive(Parser)]
+#[command(version, about)]
+pub struct Args {
+    /// Wallpaper backend to use, overrides WALRUST_BACKEND
+    #[arg(long, global = true)]
+    pub backend: Option<String>,
+
+    /// Config file to use instead of ~/.config/walrust/config.toml
+    #[arg(long, global = true)]
+    pub config: Option<PathBuf>,
+
+    #[command(subcommand)]
+    pub command: Option<Command>,
+
+    /// Image to set as wallpaper, or a directory or GNOME slideshow to pick a wallpaper from
+    pub path: Option<PathBuf>,
+}
+
+#[derive(Subcommand)]
+pub enum Command {
+    /// Set an image as wallpaper
+    Set {
+        /// Image to set as wallpaper
+        file: PathBuf,
+
+        #[command(flatten)]
+        target: TargetArgs,
+    },
+
+    /// Pick a wallpaper from a directory in the TUI
+    Pick {
+        /// Directory, or GNOME slideshow XML file, to list the images from
+        dir: Option<PathBuf>,
+    },
+
+    /// Set a random image from a directory as wallpaper
+    Random {
+        /// Directory to choose the image from
+        dir: Option<PathBuf>,
+
+        #[command(flatten)]
+        target: TargetArgs,
+
+        /// Set the same image on every monitor instead of a different one per monitor
+        #[arg(short, long)]
+        same: bool,
+
+        /// Number of recent wallpapers that are not chosen again, at most 100
+        #[arg(
+            long,
+            default_value_t = DEFAULT_HISTORY,
+            value_parser = RangedU64ValueParser::<usize>::new().range(0..=HISTORY_CAPACITY as u64)
+        )]
+        history: usize,
+    },
+
+    /// List the connected monitors
+    ListMonitors,
+
+    /// Print the wallpaper walrust last set on each monitor
+    Current,
+
+    /// Set the wallpapers walrust last set again, e.g. on login
+    Restore,
+
+    /// Rotate the wallpaper of every monitor through a directory on an interval
+    Daemon {
+        /// Directory to take the images from
+        dir: Option<PathBuf>,
+
+        /// Time between wallpapers, e.g. 30s, 10m or 1h
+        #[arg(short, long, default_value = "10m", value_parser = parse_duration)]
+        interval: Duration,
+
+        /// Show the images in a random order instead of by name
+        #[arg(short, long)]
+        shuffle: bool,
+    },
+
+    /// Control a running daemon
+    Ctl {
+        #[command(subcommand)]
+        command: CtlCommand,
+    },
+
+    /// Switch wallpapers by the time of day or the sun
+    Schedule {
+        #[command(subcommand)]
+        command: ScheduleCommand,
+
+        /// Schedule file or GNOME slideshow XML file, ~/.config/walrust/schedule.toml by default
+        #[arg(short, long, global = true)]
+        file: Option<PathBuf>,
+    },
+
+    /// Work with the config file
+    Config {
+        #[command(subcommand)]
+        command: ConfigCommand,
+    },
+}
+
+#[derive(Subcommand)]
+pub enum ConfigCommand {
+    /// Validate the config file and the directories it refers to
+    Check,
+}
+
+#[derive(Subcommand)]
+pub enum ScheduleCommand {
+    /// Keep running and switch the wallpapers at the boundaries of the schedule
+    Run,
+
+    /// Print the timeline of the schedule for today
+    Preview,
+}
+
+#[derive(Subcommand)]
+pub enum CtlCommand {
+    /// Show the next image now
+    Next,
+
+    /// Show the previous image again
+    Previous,
+
+    /// Stop rotating the wallpapers until resume
+    Pause,
+
+    /// Continue rotating the wallpapers
+    Resume,
+
+    /// Change the time between wallpapers
+    SetInterval {
+        /// Time between wallpapers, e.g. 30s, 10m or 1h
+        #[arg(value_parser = parse_duration)]
+        interval: Duration,
+    },
+
+    /// Rotate through the images of another directory
+    SetDirectory {
+        /// Directory to take the images from
+        dir: PathBuf,
+    },
+
+    /// Print the state of the daemon
+    Status,
+
+    /// Set an image as wallpaper until the next change
+    Set {
+        /// Image to set as wallpaper
+        file: PathBuf,
+
+        /// Monitor name or description (e.g. "desc:Dell Inc."), every monitor by default
+        #[arg(short, long)]
+        monitor: Option<String>,
+    },
+}
+
+#[derive(ClapArgs)]
+#[group(multiple = false)]
+pub struct TargetArgs {
+    /// Monitor name (e.g. DP-1), number from list-monitors or description (e.g. "desc:Dell Inc.")
+    #[arg(short, long)]
+    pub monitor: Option<String>,
+
+    /// Set the wallpaper on every monitor
+    #[arg(short, long)]
+    pub all: bool,
+
+    /// Set the wallpaper on the focused monitor
+    #[arg(short, long)]
+    pub focused: bool,
+}
+
+impl Args {
+    // `walrust <path>` is kept for compatibility,
+    // a directory opens the TUI and a file sets the wallpaper.
+    pub fn into_command(self) -> Result<Command> {
+        ensure!(
+            self.path.is_none() || self.command.is_none(),
+            "A path can't be combined with a command, see walrust --help"
+        );
+
+        if let Some(command) = self.command {
+            return Ok(command);
+        }
+
+        // Without a path the TUI opens the default directory from the config
+        let Some(path) = self.path else {
+            return Ok(Command::Pick { dir: None });
+        };
+
+        if path.is_dir() || GnomeSlideshowFile::is_slideshow(&path) {
+            Ok(Command::Pick { dir: Some(path) })
+        } else {
+            Ok(Command::Set {
+                file: path,
+                target: TargetArgs {
+                    monitor: None,
+                    all: false,
+                    focused: false,
+                },
+            })
+        }
+    }
+}
+
+// Accepts a number with an optional s, m or h suffix, seconds by default
+fn parse_duration(value: &str) -> Result<Duration> {
+    let value = value.trim();
+    let (number, multiplier) = match value.char_indices().last() {
+        Some((index, 's')) => (&value[..index], 1),
+        Some((index, 'm')) => (&value[..index], 60),
+        Some((index, 'h')) => (&value[..index], 60 * 60),
+        _ => (value, 1),
+    };
+
+    let seconds: u64 = number
+        .trim()
+        .parse()
+        .with_context(|| format!("Invalid duration: {}", value))?;
+
+    let duration = Duration::from_secs(
+        seconds
+            .checked_mul(multiplier)
+            .with_context(|| format!("Duration is too long: {}", value))?,
+    );
+    ensure!(!duration.is_zero(), "Duration must be greater than zero");
+
+    Ok(duration)
+}
+
+fn absolute(path: PathBuf) -> PathBuf {
+    std::path::absolute(&path).unwrap_or(path)
+}
+
+impl From<TargetArgs> for MonitorTarget {
+    fn from(target: TargetArgs) -> Self {
+        match target {
+            TargetArgs {
+                monitor: Some(selector),
+                ..
+            } => MonitorTarget::Selector(selector),
+            TargetArgs { all: true, .. } => MonitorTarget::All,
+            TargetArgs { focused: true, .. } => MonitorTarget::Focused,
+            _ => MonitorTarget::Ask,
+        }
+    }
+}
+
+// Paths are made absolute, the daemon may run in another directory
+impl From<CtlCommand> for DaemonRequest {
+    fn from(command: CtlCommand) -> Self {
+        match command {
+            CtlCommand::Next => DaemonRequest::Next,
+            CtlCommand::Previous => DaemonRequest::Previous,
+            CtlCommand::Pause => DaemonRequest::Pause,
+            CtlCommand::Resume => DaemonRequest::Resume,
+            CtlCommand::SetInterval { interval } => DaemonRequest::SetInterval {
+                seconds: interval.as_secs(),
+            },
+            CtlCommand::SetDirectory { dir } => DaemonRequest::SetDirectory {
+                path: absolute(dir),
+            },
+            CtlCommand::Status => DaemonRequest::Status,
+            CtlCommand::Set { file, monitor } => DaemonRequest::Set {
+                path: absolute(file),
+                monitor,
+            },
+        }
+    }
+}
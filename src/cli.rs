@@ -1,51 +1,208 @@
 use std::{
-    io::{self, Write},
+    io::{self, IsTerminal, Write},
     path::Path,
 };
 
-use anyhow::{Context, Result, ensure};
+use anyhow::{Context, Result, anyhow, ensure};
 
-use crate::{adapters::WallpaperService, ports::WallpaperServicePort};
+use crate::{
+    adapters::{ImageDiskRepository, StateFileRepository, WallpaperService},
+    models::monitor::Monitor,
+    ports::{ImageRepository, StateRepository, WallpaperServicePort},
+};
+
+// Which monitors a command changes
+pub enum MonitorTarget {
+    Ask,              // Ask interactively when there is more than one monitor
+    Selector(String), // See Cli::find_monitor
+    All,
+    Focused,
+}
 
-pub struct Cli {}
+pub struct Cli {
+    // Dependencies
+    wallpaper_service: WallpaperService,
+    image_repository: ImageDiskRepository,
+    state_repository: StateFileRepository,
+
+    // Data or states
+    monitors: Vec<Monitor>,
+}
 
 impl Cli {
-    pub fn run(
+    pub fn new(
         wallpaper_service: WallpaperService,
-        monitors: &[String],
-        image_path: &Path,
+        image_repository: ImageDiskRepository,
+        state_repository: StateFileRepository,
+        monitors: Vec<Monitor>,
+    ) -> Self {
+        Cli {
+            wallpaper_service,
+            image_repository,
+            state_repository,
+            monitors,
+        }
+    }
+
+    pub fn set(&self, image_path: &Path, target: MonitorTarget) -> Result<()> {
+        ensure!(image_path.is_file(), "No such file: {:?}", image_path);
+
+        for monitor in self.target_monitors(target)? {
+            self.set_wallpaper(monitor, image_path)?;
+        }
+
+        Ok(())
+    }
+
+    // Every monitor gets a different image unless `same` is set.
+    // The last `history` wallpapers are not chosen again,
+    // unless the directory doesn't have enough other images.
+    pub fn random(
+        &self,
+        dir_path: &Path,
+        target: MonitorTarget,
+        same: bool,
+        history: usize,
     ) -> Result<()> {
-        if monitors.len() == 1 {
-            return wallpaper_service.set_wallpaper(&monitors[0], image_path);
+        let images = self
+            .image_repository
+            .list_images(dir_path)
+            .with_context(|| format!("Failed to list images in {:?}", dir_path))?;
+        // Images flagged by check_headers can't be set
+        ensure!(
+            images.iter().any(|image| !image.corrupt),
+            "No images found in {:?}",
+            dir_path
+        );
+
+        let monitors = self.target_monitors(target)?;
+        let needed = if same { 1 } else { monitors.len() };
+
+        let state = self.state_repository.load().unwrap_or_default();
+        let (chosen, reused) = state.choose_random(&images, needed, history);
+        if reused {
+            eprintln!(
+                "Not enough images outside the last {} wallpapers, recent ones can be chosen again",
+                history
+            );
+        }
+
+        // When there are more monitors than images, the images are reused
+        for (monitor, image_path) in monitors.into_iter().zip(chosen.iter().cycle()) {
+            self.set_wallpaper(monitor, image_path)?;
+        }
+
+        Ok(())
+    }
+
+    pub fn list_monitors(&self) {
+        for (i, monitor) in self.monitors.iter().enumerate() {
+            match &monitor.description {
+                Some(description) => println!("{}. {monitor} ({description})", i + 1),
+                None => println!("{}. {monitor}", i + 1),
+            }
         }
+    }
 
-        let selected_monitor = Self::choose_monitor_interface(&monitors)?;
+    pub fn current(&self) -> Result<()> {
+        let state = self.state_repository.load()?;
+
+        for monitor in &self.monitors {
+            match state.get(monitor) {
+                Some(image_path) => println!("{}: {}", monitor.name, image_path.display()),
+                None => println!("{}: unknown", monitor.name),
+            }
+        }
+
+        Ok(())
+    }
+
+    pub fn restore(&self) -> Result<()> {
+        let state = self.state_repository.load()?;
+        let mut restored = 0;
+
+        for monitor in &self.monitors {
+            if let Some(image_path) = state.get(monitor) {
+                self.wallpaper_service
+                    .set_wallpaper(monitor, image_path)
+                    .with_context(|| format!("Failed to restore wallpaper of {}", monitor.name))?;
+                restored += 1;
+            }
+        }
 
         ensure!(
-            selected_monitor > 0 && selected_monitor <= monitors.len(),
-            "Invalid input, expected number between 1 and {}",
-            monitors.len()
+            restored > 0,
+            "No saved wallpaper for the connected monitors"
         );
 
-        wallpaper_service.set_wallpaper(&monitors[selected_monitor - 1], image_path)
+        Ok(())
     }
 
-    fn choose_monitor_interface(monitors: &[String]) -> Result<usize> {
+    fn set_wallpaper(&self, monitor: &Monitor, image_path: &Path) -> Result<()> {
+        self.wallpaper_service.set_wallpaper(monitor, image_path)?;
+        self.state_repository.record(monitor, image_path)
+    }
+
+    fn target_monitors(&self, target: MonitorTarget) -> Result<Vec<&Monitor>> {
+        match target {
+            MonitorTarget::All => Ok(self.monitors.iter().collect()),
+            MonitorTarget::Selector(selector) => Ok(vec![self.find_monitor(&selector)?]),
+            // e.g. "All displays", which has no focus of its own
+            MonitorTarget::Focused if self.monitors.len() == 1 => Ok(vec![&self.monitors[0]]),
+            MonitorTarget::Focused => self
+                .monitors
+                .iter()
+                .find(|monitor| monitor.focused)
+                .map(|monitor| vec![monitor])
+                .ok_or(anyhow!("Your WM doesn't report a focused monitor")),
+            MonitorTarget::Ask if self.monitors.len() == 1 => Ok(vec![&self.monitors[0]]),
+            MonitorTarget::Ask => {
+                // Keybinds and cron jobs have no one to answer the prompt,
+                // so it would block forever.
+                ensure!(
+                    io::stdin().is_terminal(),
+                    "More than one monitor detected, use --monitor, --all or --focused to choose"
+                );
+
+                Ok(vec![self.choose_monitor_interface()?])
+            }
+        }
+    }
+
+    // Accepts the number shown in the list, a connector name (e.g. DP-1)
+    // or a description selector (e.g. desc:Dell Inc. DELL U2720Q).
+    fn find_monitor(&self, selector: &str) -> Result<&Monitor> {
+        if let Ok(index) = selector.parse::<usize>() {
+            return index
+                .checked_sub(1)
+                .and_then(|index| self.monitors.get(index))
+                .ok_or(anyhow!(
+                    "Invalid monitor number, expected number between 1 and {}",
+                    self.monitors.len()
+                ));
+        }
+
+        self.monitors
+            .iter()
+            .find(|monitor| monitor.matches(selector))
+            .ok_or(anyhow!("No monitor matches {}", selector))
+    }
+
+    fn choose_monitor_interface(&self) -> Result<&Monitor> {
         let mut selected_monitor = String::new();
 
         println!("Choose monitor to change wallpaper:");
-        for (i, monitor) in monitors.iter().enumerate() {
-            println!("[{}] {monitor}", i + 1);
+        for (i, monitor) in self.monitors.iter().enumerate() {
+            match &monitor.description {
+                Some(description) => println!("[{}] {monitor} ({description})", i + 1),
+                None => println!("[{}] {monitor}", i + 1),
+            }
         }
         print!("==> ");
 
         io::stdout().flush()?;
         io::stdin().read_line(&mut selected_monitor)?;
 
-        let selected_monitor = selected_monitor.trim();
-
-        selected_monitor
-            .parse::<usize>()
-            .with_context(|| "Expected number input")
+        self.find_monitor(selected_monitor.trim())
     }
 }
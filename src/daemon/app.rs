@@ -0,0 +1,434 @@
+use std::{
+    collections::HashSet,
+    path::{Path, PathBuf},
+    sync::{
+        Arc,
+        atomic::{AtomicBool, Ordering},
+        mpsc::{self, Receiver, RecvTimeoutError},
+    },
+    time::{Duration, Instant},
+};
+
+use anyhow::{Context, Result, anyhow, ensure};
+use rand::seq::SliceRandom;
+use signal_hook::consts::{SIGINT, SIGTERM};
+
+use crate::{
+    adapters::{ImageDiskRepository, ImageService, StateFileRepository, WallpaperService},
+    daemon::{
+        control_socket::{ControlSocket, PendingRequest},
+        protocol::{DaemonReply, DaemonRequest, DaemonStatus, MonitorStatus},
+    },
+    models::{image_file::ImageFile, monitor::Monitor},
+    ports::{ImageRepository, ImageServicePort, StateRepository, WallpaperServicePort},
+};
+
+// How often the daemon checks whether it should stop while waiting
+const POLL_INTERVAL: Duration = Duration::from_millis(250);
+
+// Longer intervals could overflow the time of the next change
+const MAX_INTERVAL: Duration = Duration::from_secs(365 * 24 * 60 * 60);
+
+/*
+ * Daemon rotates the wallpaper of every monitor through the images
+ * of a directory on an interval, until it receives SIGTERM or SIGINT.
+ * Each monitor has its own playlist, so monitors show different images.
+ * It can be controlled through its control socket (see `walrust ctl`).
+ */
+
+// The order of the images shown on a monitor
+struct Playlist {
+    order: Vec<usize>, // Indexes into Daemon::images
+    position: usize,   // The next image, so the current one is at position - 1
+}
+
+impl Playlist {
+    // Monitors start at different positions so they don't show the same image
+    fn new(image_count: usize, monitor_index: usize, shuffle: bool) -> Self {
+        let mut order: Vec<usize> = (0..image_count).collect();
+
+        if shuffle {
+            order.shuffle(&mut rand::rng());
+        }
+
+        Playlist {
+            order,
+            position: monitor_index % image_count,
+        }
+    }
+
+    // Returns the index of the next image
+    fn advance(&mut self, shuffle: bool) -> usize {
+        // Start over, with a new order when shuffling
+        if self.position >= self.order.len() {
+            if shuffle {
+                self.order.shuffle(&mut rand::rng());
+            }
+            self.position = 0;
+        }
+
+        let image_index = self.order[self.position];
+        self.position += 1;
+
+        image_index
+    }
+
+    // Returns the index of the image before the current one
+    fn retreat(&mut self) -> usize {
+        let length = self.order.len();
+
+        let current = (self.position + length - 1) % length;
+        let previous = (current + length - 1) % length;
+        self.position = previous + 1;
+
+        self.order[previous]
+    }
+}
+
+fn check_interval(interval: Duration) -> Result<Duration> {
+    ensure!(!interval.is_zero(), "Interval must be greater than zero");
+    ensure!(
+        interval <= MAX_INTERVAL,
+        "Interval must be at most {} seconds",
+        MAX_INTERVAL.as_secs()
+    );
+
+    Ok(interval)
+}
+
+pub struct Daemon {
+    // Dependencies
+    wallpaper_service: WallpaperService,
+    image_repository: ImageDiskRepository,
+    image_service: ImageService,
+    state_repository: StateFileRepository,
+
+    // Data or states
+    monitors: Vec<Monitor>,
+    dir_path: PathBuf,
+    interval: Duration,
+    shuffle: bool,
+    paused: bool,
+    next_change: Instant,
+    images: Vec<ImageFile>,
+    broken_images: HashSet<PathBuf>, // Images that failed to decode
+    playlists: Vec<Playlist>,
+    wallpapers: Vec<Option<PathBuf>>, // Current wallpaper of each monitor
+
+    // Concurrency
+    terminate: Arc<AtomicBool>,
+}
+
+impl Daemon {
+    pub fn new(
+        wallpaper_service: WallpaperService,
+        image_repository: ImageDiskRepository,
+        state_repository: StateFileRepository,
+        monitors: Vec<Monitor>,
+        dir_path: PathBuf,
+        interval: Duration,
+        shuffle: bool,
+    ) -> Result<Self> {
+        let monitor_count = monitors.len();
+        let mut daemon = Daemon {
+            wallpaper_service,
+            image_repository,
+            image_service: ImageService,
+            state_repository,
+            monitors,
+            dir_path: PathBuf::new(),
+            interval: check_interval(interval)?,
+            shuffle,
+            paused: false,
+            next_change: Instant::now(),
+            images: Vec::new(),
+            broken_images: HashSet::new(),
+            playlists: Vec::new(),
+            wallpapers: vec![None; monitor_count],
+            terminate: Arc::new(AtomicBool::new(false)),
+        };
+
+        daemon.load_images(dir_path)?;
+
+        Ok(daemon)
+    }
+
+    pub fn run(&mut self, socket_path: &Path) -> Result<()> {
+        for signal in [SIGTERM, SIGINT] {
+            signal_hook::flag::register(signal, Arc::clone(&self.terminate))
+                .context("Failed to register signal handler")?;
+        }
+
+        let (request_tx, request_rx) = mpsc::channel::<PendingRequest>();
+        // Removes the socket file when the daemon stops
+        let _control_socket = ControlSocket::bind(socket_path, request_tx)?;
+
+        while !self.should_terminate() {
+            // The directory may be fixed or changed before the next interval
+            if !self.paused
+                && Instant::now() >= self.next_change
+                && let Err(error) = self.next()
+            {
+                eprintln!("{:#}", error);
+            }
+
+            self.wait_for_request(&request_rx);
+        }
+
+        Ok(())
+    }
+
+    fn should_terminate(&self) -> bool {
+        self.terminate.load(Ordering::Relaxed)
+    }
+
+    // Waits in small steps so a signal doesn't have to wait for the whole interval
+    fn wait_for_request(&mut self, request_rx: &Receiver<PendingRequest>) {
+        let timeout = if self.paused {
+            POLL_INTERVAL
+        } else {
+            POLL_INTERVAL.min(self.next_change.saturating_duration_since(Instant::now()))
+        };
+
+        match request_rx.recv_timeout(timeout) {
+            Ok((request, reply_tx)) => {
+                let reply = self
+                    .request(request)
+                    .unwrap_or_else(|error| DaemonReply::Error {
+                        message: format!("{:#}", error),
+                    });
+                let _ = reply_tx.send(reply);
+            }
+            Err(RecvTimeoutError::Timeout) | Err(RecvTimeoutError::Disconnected) => {}
+        }
+    }
+
+    fn request(&mut self, request: DaemonRequest) -> Result<DaemonReply> {
+        match request {
+            DaemonRequest::Next => self.next()?,
+            DaemonRequest::Previous => self.previous()?,
+            DaemonRequest::Pause => self.paused = true,
+            DaemonRequest::Resume => {
+                self.paused = false;
+                self.next_change = Instant::now() + self.interval;
+            }
+            DaemonRequest::SetInterval { seconds } => {
+                self.interval = check_interval(Duration::from_secs(seconds))?;
+                self.next_change = Instant::now() + self.interval;
+            }
+            DaemonRequest::SetDirectory { path } => {
+                self.load_images(path)?;
+                self.next()?;
+            }
+            DaemonRequest::Status => {
+                return Ok(DaemonReply::Status {
+                    status: self.status(),
+                });
+            }
+            DaemonRequest::Set { path, monitor } => self.set(&path, monitor.as_deref())?,
+        }
+
+        Ok(DaemonReply::Ok)
+    }
+
+    fn status(&self) -> DaemonStatus {
+        DaemonStatus {
+            directory: self.dir_path.clone(),
+            interval_seconds: self.interval.as_secs(),
+            shuffle: self.shuffle,
+            paused: self.paused,
+            monitors: self
+                .monitors
+                .iter()
+                .zip(&self.wallpapers)
+                .map(|(monitor, wallpaper)| MonitorStatus {
+                    monitor: monitor.name.clone(),
+                    wallpaper: wallpaper.clone(),
+                })
+                .collect(),
+        }
+    }
+
+    // The images are only replaced when one of the new images can be shown,
+    // otherwise the daemon keeps rotating through the current directory
+    fn load_images(&mut self, dir_path: PathBuf) -> Result<()> {
+        let images = self
+            .image_repository
+            .list_images(&dir_path)
+            .with_context(|| format!("Failed to list images in {:?}", dir_path))?;
+        ensure!(!images.is_empty(), "No images found in {:?}", dir_path);
+        ensure!(
+            images
+                .iter()
+                .any(|image| !image.corrupt && self.image_service.decode(&image.path).is_ok()),
+            "None of the images in {:?} can be decoded",
+            dir_path
+        );
+
+        self.dir_path = dir_path;
+        self.broken_images = images
+            .iter()
+            .filter(|image| image.corrupt)
+            .map(|image| image.path.clone())
+            .collect();
+        self.images = images;
+        self.playlists = (0..self.monitors.len())
+            .map(|i| Playlist::new(self.images.len(), i, self.shuffle))
+            .collect();
+
+        Ok(())
+    }
+
+    // Sets the next image of every monitor
+    fn next(&mut self) -> Result<()> {
+        self.change(true)
+    }
+
+    // Sets the previous image of every monitor
+    fn previous(&mut self) -> Result<()> {
+        self.change(false)
+    }
+
+    fn change(&mut self, forward: bool) -> Result<()> {
+        self.next_change = Instant::now() + self.interval;
+
+        for monitor_index in 0..self.monitors.len() {
+            let Some(image_path) = self.find_image(monitor_index, forward) else {
+                ensure!(
+                    self.broken_images.len() < self.images.len(),
+                    "None of the images in {:?} can be decoded",
+                    self.dir_path
+                );
+                continue;
+            };
+
+            // A failure shouldn't stop the slideshow, the next image may work
+            if let Err(error) = self.set_wallpaper(monitor_index, &image_path) {
+                eprintln!("{:#}", error);
+            }
+        }
+
+        Ok(())
+    }
+
+    // Sets an image that is not in the playlist, until the next change
+    fn set(&mut self, image_path: &Path, selector: Option<&str>) -> Result<()> {
+        ensure!(image_path.is_file(), "No such file: {:?}", image_path);
+
+        let monitor_indexes: Vec<usize> = match selector {
+            Some(selector) => vec![
+                self.monitors
+                    .iter()
+                    .position(|monitor| monitor.matches(selector))
+                    .ok_or(anyhow!("No monitor matches {}", selector))?,
+            ],
+            None => (0..self.monitors.len()).collect(),
+        };
+
+        for monitor_index in monitor_indexes {
+            self.set_wallpaper(monitor_index, image_path)?;
+        }
+
+        Ok(())
+    }
+
+    // Moves the playlist of the monitor to the next (or previous)
+    // image that can be decoded
+    fn find_image(&mut self, monitor_index: usize, forward: bool) -> Option<PathBuf> {
+        for _ in 0..self.images.len() {
+            let image_path = if forward {
+                self.advance(monitor_index)
+            } else {
+                self.retreat(monitor_index)
+            };
+
+            if self.broken_images.contains(&image_path) {
+                continue;
+            }
+
+            if let Err(error) = self.image_service.decode(&image_path) {
+                eprintln!("Skipping {:?}: {:#}", image_path, error);
+                self.broken_images.insert(image_path);
+                continue;
+            }
+
+            return Some(image_path);
+        }
+
+        None
+    }
+
+    fn advance(&mut self, monitor_index: usize) -> PathBuf {
+        let image_index = self.playlists[monitor_index].advance(self.shuffle);
+        self.images[image_index].path.clone()
+    }
+
+    fn retreat(&mut self, monitor_index: usize) -> PathBuf {
+        let image_index = self.playlists[monitor_index].retreat();
+        self.images[image_index].path.clone()
+    }
+
+    fn set_wallpaper(&mut self, monitor_index: usize, image_path: &Path) -> Result<()> {
+        let monitor = &self.monitors[monitor_index];
+
+        self.wallpaper_service.set_wallpaper(monitor, image_path)?;
+        self.state_repository.record(monitor, image_path)?;
+        self.wallpapers[monitor_index] = Some(image_path.to_path_buf());
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn interval_must_be_positive_and_at_most_a_year() {
+        assert!(check_interval(Duration::ZERO).is_err());
+        assert!(check_interval(Duration::from_secs(u64::MAX)).is_err());
+        assert!(check_interval(MAX_INTERVAL + Duration::from_secs(1)).is_err());
+        assert_eq!(
+            check_interval(Duration::from_secs(60)).unwrap(),
+            Duration::from_secs(60)
+        );
+        assert_eq!(check_interval(MAX_INTERVAL).unwrap(), MAX_INTERVAL);
+    }
+
+    #[test]
+    fn monitors_start_at_different_images() {
+        let first = Playlist::new(3, 0, false);
+        let second = Playlist::new(3, 1, false);
+        let fourth = Playlist::new(3, 3, false);
+
+        assert_eq!(first.position, 0);
+        assert_eq!(second.position, 1);
+        assert_eq!(fourth.position, 0);
+    }
+
+    #[test]
+    fn playlist_wraps_around_in_both_directions() {
+        let mut playlist = Playlist::new(3, 0, false);
+
+        let forward: Vec<usize> = (0..4).map(|_| playlist.advance(false)).collect();
+        assert_eq!(forward, [0, 1, 2, 0]);
+
+        // The current image is 0, so the previous one is the last image
+        assert_eq!(playlist.retreat(), 2);
+        assert_eq!(playlist.retreat(), 1);
+        assert_eq!(playlist.advance(false), 2);
+    }
+
+    #[test]
+    fn shuffled_playlist_shows_every_image_once_per_round() {
+        let mut playlist = Playlist::new(5, 0, true);
+
+        let mut round: Vec<usize> = (0..5).map(|_| playlist.advance(true)).collect();
+        round.sort();
+        assert_eq!(round, [0, 1, 2, 3, 4]);
+
+        let mut next_round: Vec<usize> = (0..5).map(|_| playlist.advance(true)).collect();
+        next_round.sort();
+        assert_eq!(next_round, [0, 1, 2, 3, 4]);
+    }
+}
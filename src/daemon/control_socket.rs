@@ -0,0 +1,198 @@
+use std::{
+    env, fs,
+    io::{BufRead, BufReader, Write},
+    os::unix::net::{UnixListener, UnixStream},
+    path::{Path, PathBuf},
+    sync::mpsc::{self, Sender},
+    thread,
+};
+
+use anyhow::{Context, Result, anyhow, bail};
+
+use super::protocol::{DaemonReply, DaemonRequest};
+
+// A request waiting to be handled by the daemon, with the channel to reply to
+pub type PendingRequest = (DaemonRequest, Sender<DaemonReply>);
+
+/*
+ * ControlSocket is the Unix socket the daemon listens on.
+ * Each connection is read on a separate thread and forwarded to the daemon loop,
+ * so the daemon state is only ever touched by the daemon itself.
+ */
+
+pub struct ControlSocket {
+    path: PathBuf,
+}
+
+impl ControlSocket {
+    // $XDG_RUNTIME_DIR/walrust/daemon.sock
+    pub fn default_path() -> Result<PathBuf> {
+        let runtime_dir = env::var_os("XDG_RUNTIME_DIR")
+            .map(PathBuf::from)
+            .ok_or(anyhow!("XDG_RUNTIME_DIR is not set"))?;
+
+        Ok(runtime_dir.join("walrust").join("daemon.sock"))
+    }
+
+    pub fn bind(path: impl Into<PathBuf>, request_tx: Sender<PendingRequest>) -> Result<Self> {
+        let path = path.into();
+
+        if UnixStream::connect(&path).is_ok() {
+            bail!("walrust daemon is already running on {:?}", path);
+        }
+
+        // The socket of a daemon that didn't stop cleanly
+        if path.exists() {
+            fs::remove_file(&path).with_context(|| format!("Failed to remove {:?}", path))?;
+        }
+
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent).with_context(|| format!("Failed to create {:?}", parent))?;
+        }
+
+        let listener =
+            UnixListener::bind(&path).with_context(|| format!("Failed to bind {:?}", path))?;
+
+        thread::spawn(move || {
+            for stream in listener.incoming().flatten() {
+                let request_tx = request_tx.clone();
+                thread::spawn(move || Self::handle_connection(stream, request_tx));
+            }
+        });
+
+        Ok(ControlSocket { path })
+    }
+
+    // Sends a request to a running daemon and waits for the reply
+    pub fn request(path: &Path, request: &DaemonRequest) -> Result<DaemonReply> {
+        let mut stream = UnixStream::connect(path).with_context(|| {
+            format!(
+                "Failed to connect to {:?}, is walrust daemon running?",
+                path
+            )
+        })?;
+
+        let mut line = serde_json::to_string(request)?;
+        line.push('\n');
+        stream.write_all(line.as_bytes())?;
+
+        let mut reply = String::new();
+        BufReader::new(stream).read_line(&mut reply)?;
+
+        serde_json::from_str(&reply).context("Failed to parse walrust daemon reply")
+    }
+
+    fn handle_connection(stream: UnixStream, request_tx: Sender<PendingRequest>) -> Result<()> {
+        let mut writer = stream.try_clone()?;
+        let reader = BufReader::new(stream);
+
+        for line in reader.lines() {
+            let line = line?;
+            if line.trim().is_empty() {
+                continue;
+            }
+
+            let reply = match serde_json::from_str::<DaemonRequest>(&line) {
+                Ok(request) => {
+                    let (reply_tx, reply_rx) = mpsc::channel();
+                    request_tx.send((request, reply_tx))?;
+                    reply_rx.recv()?
+                }
+                Err(error) => DaemonReply::Error {
+                    message: format!("Invalid request: {}", error),
+                },
+            };
+
+            let mut reply_line = serde_json::to_string(&reply)?;
+            reply_line.push('\n');
+            writer.write_all(reply_line.as_bytes())?;
+        }
+
+        Ok(())
+    }
+}
+
+impl Drop for ControlSocket {
+    fn drop(&mut self) {
+        let _ = fs::remove_file(&self.path);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{sync::mpsc::Receiver, time::Duration};
+
+    use super::*;
+    use crate::test_utils::TempDir;
+
+    // Answers like the daemon loop would
+    fn serve(request_rx: Receiver<PendingRequest>) {
+        thread::spawn(move || {
+            for (request, reply_tx) in request_rx {
+                let reply = match request {
+                    DaemonRequest::SetInterval { seconds: 0 } => DaemonReply::Error {
+                        message: String::from("Interval must be greater than zero"),
+                    },
+                    _ => DaemonReply::Ok,
+                };
+                let _ = reply_tx.send(reply);
+            }
+        });
+    }
+
+    #[test]
+    fn request_gets_the_daemon_reply() {
+        let dir = TempDir::new("control");
+        let path = dir.join("daemon.sock");
+        let (request_tx, request_rx) = mpsc::channel();
+        let _socket = ControlSocket::bind(&path, request_tx).unwrap();
+        serve(request_rx);
+
+        assert_eq!(
+            ControlSocket::request(&path, &DaemonRequest::Next).unwrap(),
+            DaemonReply::Ok
+        );
+        assert_eq!(
+            ControlSocket::request(&path, &DaemonRequest::SetInterval { seconds: 0 }).unwrap(),
+            DaemonReply::Error {
+                message: String::from("Interval must be greater than zero")
+            }
+        );
+    }
+
+    #[test]
+    fn invalid_request_gets_an_error_reply() {
+        let dir = TempDir::new("control");
+        let path = dir.join("daemon.sock");
+        let (request_tx, request_rx) = mpsc::channel();
+        let _socket = ControlSocket::bind(&path, request_tx).unwrap();
+        serve(request_rx);
+
+        let mut stream = UnixStream::connect(&path).unwrap();
+        stream
+            .set_read_timeout(Some(Duration::from_secs(5)))
+            .unwrap();
+        stream.write_all(b"{\"command\":\"dance\"}\n").unwrap();
+
+        let mut reply = String::new();
+        BufReader::new(stream).read_line(&mut reply).unwrap();
+
+        assert!(matches!(
+            serde_json::from_str(&reply).unwrap(),
+            DaemonReply::Error { message } if message.starts_with("Invalid request")
+        ));
+    }
+
+    #[test]
+    fn second_daemon_is_refused_and_socket_is_removed() {
+        let dir = TempDir::new("control");
+        let path = dir.join("daemon.sock");
+        let (request_tx, _request_rx) = mpsc::channel();
+        let socket = ControlSocket::bind(&path, request_tx.clone()).unwrap();
+
+        assert!(ControlSocket::bind(&path, request_tx).is_err());
+
+        drop(socket);
+        assert!(!path.exists());
+    }
+}
@@ -0,0 +1,4 @@
+pub mod app;
+pub mod control_socket;
+pub mod protocol;
+pub mod scheduler;
@@ -0,0 +1,138 @@
+use std::path::PathBuf;
+
+use serde::{Deserialize, Serialize};
+
+use crate::models::os_path;
+
+/*
+ * The daemon is controlled with one JSON object per line,
+ * every request gets exactly one reply line, e.g.
+ * -> {"command":"set-interval","seconds":60}
+ * <- {"result":"ok"}
+ */
+
+#[derive(Debug, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "command", rename_all = "kebab-case")]
+pub enum DaemonRequest {
+    Next,
+    Previous,
+    Pause,
+    Resume,
+    SetInterval {
+        seconds: u64,
+    },
+    SetDirectory {
+        #[serde(with = "os_path")]
+        path: PathBuf,
+    },
+    Status,
+    Set {
+        #[serde(with = "os_path")]
+        path: PathBuf,
+        monitor: Option<String>, // Every monitor when it's not set
+    },
+}
+
+#[derive(Debug, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "result", rename_all = "kebab-case")]
+pub enum DaemonReply {
+    Ok,
+    Status { status: DaemonStatus },
+    Error { message: String },
+}
+
+#[derive(Debug, PartialEq, Serialize, Deserialize)]
+pub struct DaemonStatus {
+    #[serde(with = "os_path")]
+    pub directory: PathBuf,
+    pub interval_seconds: u64,
+    pub shuffle: bool,
+    pub paused: bool,
+    pub monitors: Vec<MonitorStatus>,
+}
+
+#[derive(Debug, PartialEq, Serialize, Deserialize)]
+pub struct MonitorStatus {
+    pub monitor: String,
+    #[serde(with = "os_path::option")]
+    pub wallpaper: Option<PathBuf>,
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{ffi::OsString, os::unix::ffi::OsStringExt};
+
+    use super::*;
+
+    fn round_trip<T: Serialize + for<'de> Deserialize<'de>>(value: &T) -> T {
+        serde_json::from_str(&serde_json::to_string(value).unwrap()).unwrap()
+    }
+
+    #[test]
+    fn requests_use_the_documented_format() {
+        assert_eq!(
+            serde_json::to_string(&DaemonRequest::SetInterval { seconds: 60 }).unwrap(),
+            r#"{"command":"set-interval","seconds":60}"#
+        );
+        assert_eq!(
+            serde_json::from_str::<DaemonRequest>(r#"{"command":"next"}"#).unwrap(),
+            DaemonRequest::Next
+        );
+        assert_eq!(
+            serde_json::to_string(&DaemonReply::Ok).unwrap(),
+            r#"{"result":"ok"}"#
+        );
+    }
+
+    #[test]
+    fn requests_round_trip() {
+        let non_utf8 = PathBuf::from(OsString::from_vec(b"/images/caf\xe9.png".to_vec()));
+        let requests = [
+            DaemonRequest::Next,
+            DaemonRequest::Previous,
+            DaemonRequest::Pause,
+            DaemonRequest::Resume,
+            DaemonRequest::SetInterval { seconds: 3600 },
+            DaemonRequest::SetDirectory {
+                path: PathBuf::from("/images"),
+            },
+            DaemonRequest::Status,
+            DaemonRequest::Set {
+                path: non_utf8,
+                monitor: Some(String::from("DP-1")),
+            },
+        ];
+
+        for request in requests {
+            assert_eq!(round_trip(&request), request);
+        }
+    }
+
+    #[test]
+    fn replies_round_trip() {
+        let status = DaemonReply::Status {
+            status: DaemonStatus {
+                directory: PathBuf::from(OsString::from_vec(b"/images/\xff".to_vec())),
+                interval_seconds: 600,
+                shuffle: true,
+                paused: false,
+                monitors: vec![
+                    MonitorStatus {
+                        monitor: String::from("DP-1"),
+                        wallpaper: Some(PathBuf::from("/images/a.png")),
+                    },
+                    MonitorStatus {
+                        monitor: String::from("DP-2"),
+                        wallpaper: None,
+                    },
+                ],
+            },
+        };
+        let error = DaemonReply::Error {
+            message: String::from("No images found"),
+        };
+
+        assert_eq!(round_trip(&status), status);
+        assert_eq!(round_trip(&error), error);
+    }
+}
@@ -0,0 +1,212 @@
+use std::{
+    path::Path,
+    sync::{
+        Arc,
+        atomic::{AtomicBool, Ordering},
+    },
+    thread,
+    time::Duration,
+};
+
+use anyhow::{Context, Result, ensure};
+use chrono::{Local, NaiveDateTime};
+use signal_hook::consts::{SIGINT, SIGTERM};
+
+use crate::{
+    models::{
+        monitor::Monitor, schedule::Schedule, slideshow::Slideshow, timetable::Timetable,
+        wallpaper_state::DEFAULT_HISTORY,
+    },
+    ports::{ImageRepository, StateRepository, WallpaperServicePort},
+};
+
+// How often the clock is checked, so suspend and signals are noticed quickly
+const POLL_INTERVAL: Duration = Duration::from_millis(250);
+
+/*
+ * Scheduler sets the wallpaper of every monitor from the active entry
+ * of a schedule or slideshow and switches it at the boundaries of the entries,
+ * until it receives SIGTERM or SIGINT.
+ */
+
+pub struct Scheduler<W, I, S> {
+    // Dependencies
+    wallpaper_service: W,
+    image_repository: I,
+    state_repository: S,
+
+    // Data or states
+    monitors: Vec<Monitor>,
+    timetable: Timetable,
+
+    // Concurrency
+    terminate: Arc<AtomicBool>,
+}
+
+impl<W, I, S> Scheduler<W, I, S>
+where
+    W: WallpaperServicePort,
+    I: ImageRepository,
+    S: StateRepository,
+{
+    pub fn new(
+        wallpaper_service: W,
+        image_repository: I,
+        state_repository: S,
+        monitors: Vec<Monitor>,
+        timetable: Timetable,
+    ) -> Self {
+        Scheduler {
+            wallpaper_service,
+            image_repository,
+            state_repository,
+            monitors,
+            timetable,
+            terminate: Arc::new(AtomicBool::new(false)),
+        }
+    }
+
+    pub fn run(&self) -> Result<()> {
+        for signal in [SIGTERM, SIGINT] {
+            signal_hook::flag::register(signal, Arc::clone(&self.terminate))
+                .context("Failed to register signal handler")?;
+        }
+
+        let mut shown: Option<&Path> = None;
+        let mut next_change = Local::now().naive_local();
+
+        while !self.terminate.load(Ordering::Relaxed) {
+            let now = Local::now().naive_local();
+
+            if now >= next_change {
+                let active = self.timetable.active(now);
+                if active != shown {
+                    if let Some(path) = active {
+                        // A failure shouldn't stop the schedule, the next entry may work
+                        if let Err(error) = self.apply(path) {
+                            eprintln!("{:#}", error);
+                        }
+                    }
+                    shown = active;
+                }
+
+                next_change = self.timetable.next_change(now);
+            }
+
+            thread::sleep(POLL_INTERVAL);
+        }
+
+        Ok(())
+    }
+
+    fn apply(&self, path: &Path) -> Result<()> {
+        if path.is_dir() {
+            return self.apply_random(path);
+        }
+
+        ensure!(path.is_file(), "No such file: {:?}", path);
+        for monitor in &self.monitors {
+            self.set_wallpaper(monitor, path)?;
+        }
+
+        Ok(())
+    }
+
+    // Every monitor gets a different image, recent wallpapers are avoided when possible
+    fn apply_random(&self, dir_path: &Path) -> Result<()> {
+        let images = self
+            .image_repository
+            .list_images(dir_path)
+            .with_context(|| format!("Failed to list images in {:?}", dir_path))?;
+        ensure!(
+            images.iter().any(|image| !image.corrupt),
+            "No images found in {:?}",
+            dir_path
+        );
+
+        let state = self.state_repository.load().unwrap_or_default();
+        let (chosen, _) = state.choose_random(&images, self.monitors.len(), DEFAULT_HISTORY);
+
+        for (monitor, image_path) in self.monitors.iter().zip(chosen.iter().cycle()) {
+            self.set_wallpaper(monitor, image_path)?;
+        }
+
+        Ok(())
+    }
+
+    fn set_wallpaper(&self, monitor: &Monitor, image_path: &Path) -> Result<()> {
+        self.wallpaper_service.set_wallpaper(monitor, image_path)?;
+        self.state_repository.record(monitor, image_path)
+    }
+}
+
+// Prints the entries of the day, marking the active one
+pub fn print_timeline(timetable: &Timetable, now: NaiveDateTime) {
+    match timetable {
+        Timetable::Schedule(schedule) => print_schedule(schedule, now),
+        Timetable::Slideshow(slideshow) => print_slideshow(slideshow, now),
+    }
+}
+
+fn print_schedule(schedule: &Schedule, now: NaiveDateTime) {
+    let date = now.date();
+
+    match schedule.sun_times(date) {
+        Some(sun_times) => println!(
+            "{}, sunrise {}, sunset {}",
+            date,
+            sun_times.sunrise.format("%H:%M"),
+            sun_times.sunset.format("%H:%M")
+        ),
+        None if schedule.location.is_some() => {
+            println!("{}, the sun doesn't rise or set today", date)
+        }
+        None => println!("{}", date),
+    }
+
+    let timeline = schedule.timeline(date);
+    let active = schedule.active(now);
+
+    for slot in &timeline {
+        let entry = &schedule.entries[slot.entry_index];
+        let marker = if Some(slot.entry_index) == active {
+            "  <- now"
+        } else {
+            ""
+        };
+
+        println!(
+            "{} - {}  {:<12} {}{}",
+            slot.start.format("%H:%M"),
+            slot.end.format("%H:%M"),
+            entry.start.to_string(),
+            entry.path.display(),
+            marker
+        );
+    }
+
+    for (index, entry) in schedule.entries.iter().enumerate() {
+        if !timeline.iter().any(|slot| slot.entry_index == index) {
+            println!("Skipped today: {} {}", entry.start, entry.path.display());
+        }
+    }
+}
+
+fn print_slideshow(slideshow: &Slideshow, now: NaiveDateTime) {
+    println!("{}", now.date());
+
+    for (time, index) in slideshow.timeline(now.date()) {
+        let marker = if time <= now && now < slideshow.next_change(time) {
+            "  <- now"
+        } else {
+            ""
+        };
+
+        println!(
+            "{}  {}{}",
+            time.format("%H:%M:%S"),
+            slideshow.slides[index].path.display(),
+            marker
+        );
+    }
+}
@@ -1,55 +1,304 @@
-use std::{env, path::PathBuf};
+use std::path::{Path, PathBuf};
 
-use anyhow::{anyhow, ensure};
+use anyhow::{anyhow, bail, ensure};
+use chrono::Local;
+use clap::Parser;
 use ratatui_image::picker::Picker;
 
 use crate::{
-    adapters::{MonitorProvider, WallpaperService},
+    adapters::{
+        ConfigFileRepository, GnomeSlideshowFile, HyprpaperPersistence, ImageDiskRepository,
+        MonitorProvider, ScheduleFileRepository, StateFileRepository, SwwwTransition,
+        WallpaperService,
+    },
+    args::{Args, Command, ConfigCommand, CtlCommand, ScheduleCommand},
     cli::Cli,
-    models::desktop::Desktop,
-    ports::MonitorProviderPort,
+    daemon::{
+        app::Daemon,
+        control_socket::ControlSocket,
+        protocol::{DaemonReply, DaemonRequest},
+        scheduler::{self, Scheduler},
+    },
+    models::{
+        backend::Backend, config::Config, desktop::Desktop, fit_mode::FitMode, monitor::Monitor,
+        timetable::Timetable,
+    },
+    ports::{ConfigRepository, MonitorProviderPort, ScheduleRepository},
     tui::{app::App, messages::Messages},
 };
 
 mod adapters;
+mod args;
 mod cli;
+mod daemon;
 mod models;
 mod ports;
+#[cfg(test)]
+mod test_utils;
 mod tui;
 
 fn main() -> anyhow::Result<()> {
-    let mut args = env::args();
-    let path = args
-        .nth(1)
-        .map(PathBuf::from)
-        .ok_or_else(|| anyhow::anyhow!("No arguments are provided"))?;
+    let args = Args::parse();
 
-    ensure!(path.exists(), "No such file or directory");
+    // JPEG XL is decoded by jxl-oxide through the image crate
+    #[cfg(feature = "jxl")]
+    jxl_oxide::integration::register_image_decoding_hook();
+
+    let config_repository = match &args.config {
+        Some(path) => ConfigFileRepository::new(path),
+        None => ConfigFileRepository::from_default_path()?,
+    };
+
+    // Checking reports the problems itself, so it runs before the config is used
+    if let Some(Command::Config {
+        command: ConfigCommand::Check,
+    }) = &args.command
+    {
+        return config_check(&config_repository);
+    }
+
+    let backend_name = args.backend.clone();
+    let command = args.into_command()?;
+
+    // The daemon already knows the desktop and monitors
+    if let Command::Ctl { command } = command {
+        return ctl(command);
+    }
+
+    if let Command::Schedule {
+        command: ScheduleCommand::Preview,
+        file,
+    } = &command
+    {
+        let timetable = load_schedule(file.clone())?;
+        scheduler::print_timeline(&timetable, Local::now().naive_local());
+        return Ok(());
+    }
+
+    // The commands above work even when the config is broken
+    let config = config_repository.load()?;
+
+    // --backend, WALRUST_BACKEND or the config can be used to choose the backend explicitly
+    let backend = match &backend_name {
+        Some(backend_name) => {
+            let backend = Backend::from(backend_name);
+            ensure!(!backend.is_unknown(), "Unknown backend: {}", backend_name);
+            backend
+        }
+        None => match Backend::from_env("WALRUST_BACKEND")? {
+            Backend::Unknown => config
+                .general
+                .backend
+                .as_deref()
+                .map(Backend::from)
+                .unwrap_or(Backend::Unknown),
+            backend => backend,
+        },
+    };
+
+    let directories = &config.directories;
+    let directory = |dir: Option<PathBuf>, command_dir: &Option<PathBuf>| {
+        directories.resolve(dir, command_dir).ok_or(anyhow!(
+            "No directory is given and there is no default directory in {:?}, see walrust --help",
+            config_repository.path()
+        ))
+    };
 
     let desktop = Desktop::detect();
-    ensure!(!desktop.is_unknown(), "Your WM is unknown or not supported");
+    let backend = match backend {
+        Backend::Unknown => Backend::detect(&desktop),
+        backend => backend,
+    };
+    ensure!(
+        !desktop.is_unknown() || !backend.is_unknown(),
+        "Your WM is unknown or not supported"
+    );
 
-    let monitors = MonitorProvider::from(&desktop)
+    let monitors = MonitorProvider::from(&desktop, &backend)
         .ok_or(anyhow!("Your WM is not supported"))?
         .get_monitors()?;
     ensure!(!monitors.is_empty(), "No monitor detected");
 
-    let wallpaper_service =
-        WallpaperService::from(&desktop).ok_or(anyhow!("Your WM is not supported"))?;
+    // The dry-run diff is printed to stdout, where the TUI draws
+    let persistence = HyprpaperPersistence::from_env("WALRUST_PERSIST")?;
+    ensure!(
+        !(persistence == HyprpaperPersistence::DryRun && matches!(command, Command::Pick { .. })),
+        "WALRUST_PERSIST=dry-run can't be used with pick, use set or random to see the diff"
+    );
 
-    if path.is_dir() {
-        let picker = Picker::from_query_stdio()?;
-        let terminal = ratatui::init();
-        let mut messages = Messages::new(250);
+    let wallpaper_service = WallpaperService::from(&backend)
+        .ok_or(anyhow!("No supported wallpaper backend found"))?
+        .with_transition(SwwwTransition::from_env()?)
+        .with_fit_mode(FitMode::from_env("WALRUST_FIT_MODE")?)
+        .with_persistence(persistence);
 
-        messages.start_event_listener();
+    let image_repository = ImageDiskRepository::new(&config.images);
+    let state_repository = StateFileRepository::new(StateFileRepository::default_path()?);
 
-        let app = App::new(messages, path, monitors, picker, wallpaper_service)?.run(terminal);
+    if let Command::Pick { dir } = &command {
+        let dir = directory(dir.clone(), &directories.pick)?;
+        return pick(&dir, monitors, wallpaper_service, state_repository, &config);
+    }
+
+    if let Command::Daemon {
+        dir,
+        interval,
+        shuffle,
+    } = command
+    {
+        return Daemon::new(
+            wallpaper_service,
+            image_repository,
+            state_repository,
+            monitors,
+            directory(dir, &directories.daemon)?,
+            interval,
+            shuffle,
+        )?
+        .run(&ControlSocket::default_path()?);
+    }
+
+    if let Command::Schedule { file, .. } = command {
+        return Scheduler::new(
+            wallpaper_service,
+            image_repository,
+            state_repository,
+            monitors,
+            load_schedule(file)?,
+        )
+        .run();
+    }
 
-        ratatui::restore();
+    let cli = Cli::new(
+        wallpaper_service,
+        image_repository,
+        state_repository,
+        monitors,
+    );
 
-        app
-    } else {
-        Cli::run(wallpaper_service, &monitors, &path)
+    match command {
+        Command::Set { file, target } => cli.set(&file, target.into()),
+        Command::Random {
+            dir,
+            target,
+            same,
+            history,
+        } => cli.random(
+            &directory(dir, &directories.random)?,
+            target.into(),
+            same,
+            history,
+        ),
+        Command::ListMonitors => {
+            cli.list_monitors();
+            Ok(())
+        }
+        Command::Current => cli.current(),
+        Command::Restore => cli.restore(),
+        Command::Pick { .. }
+        | Command::Daemon { .. }
+        | Command::Ctl { .. }
+        | Command::Schedule { .. }
+        | Command::Config { .. } => unreachable!(),
     }
 }
+
+fn pick(
+    dir: &Path,
+    monitors: Vec<Monitor>,
+    wallpaper_service: WallpaperService,
+    state_repository: StateFileRepository,
+    config: &Config,
+) -> anyhow::Result<()> {
+    ensure!(
+        dir.is_dir() || GnomeSlideshowFile::is_slideshow(dir),
+        "No such directory: {:?}",
+        dir
+    );
+
+    let picker = Picker::from_query_stdio()?;
+    let terminal = ratatui::init();
+    let mut messages = Messages::new(config.tui.tick_rate_ms);
+
+    messages.start_event_listener();
+
+    let app = App::new(
+        messages,
+        dir.to_path_buf(),
+        monitors,
+        picker,
+        wallpaper_service,
+        state_repository,
+        config,
+    )?
+    .run(terminal);
+
+    ratatui::restore();
+
+    app
+}
+
+fn load_schedule(file: Option<PathBuf>) -> anyhow::Result<Timetable> {
+    let path = match file {
+        Some(path) => path,
+        None => ScheduleFileRepository::default_path()?,
+    };
+
+    ScheduleFileRepository::new(path).load()
+}
+
+fn ctl(command: CtlCommand) -> anyhow::Result<()> {
+    let socket_path = ControlSocket::default_path()?;
+    let request = DaemonRequest::from(command);
+
+    match ControlSocket::request(&socket_path, &request)? {
+        DaemonReply::Ok => Ok(()),
+        DaemonReply::Status { status } => {
+            println!("Directory: {}", status.directory.display());
+            println!("Interval: {}s", status.interval_seconds);
+            println!("Shuffle: {}", status.shuffle);
+            println!("Paused: {}", status.paused);
+
+            for monitor_status in status.monitors {
+                let wallpaper = monitor_status
+                    .wallpaper
+                    .as_deref()
+                    .map(Path::display)
+                    .map(|path| path.to_string())
+                    .unwrap_or(String::from("-"));
+                println!("{}: {}", monitor_status.monitor, wallpaper);
+            }
+
+            Ok(())
+        }
+        DaemonReply::Error { message } => bail!(message),
+    }
+}
+
+// Also checks that the configured directories exist
+fn config_check(config_repository: &ConfigFileRepository) -> anyhow::Result<()> {
+    let path = config_repository.path();
+
+    if config_repository.uses_defaults() {
+        println!(
+            "No config file at {}, the defaults are used",
+            path.display()
+        );
+        return Ok(());
+    }
+
+    let config = config_repository.load()?;
+    let mut problems = 0;
+
+    for (name, dir) in config.directories.all() {
+        if !dir.is_dir() {
+            eprintln!("directories.{}: no such directory {:?}", name, dir);
+            problems += 1;
+        }
+    }
+
+    ensure!(problems == 0, "{:?} has {} problem(s)", path, problems);
+    println!("{} is valid", path.display());
+
+    Ok(())
+}
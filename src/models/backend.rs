@@ -0,0 +1,102 @@
+use std::{env, path::Path, process::Command};
+
+use anyhow::{Result, ensure};
+
+use crate::models::desktop::Desktop;
+
+/*
+ * Backend is the program that actually draws the wallpaper.
+ * A desktop usually has its own default backend (e.g. Hyprland uses Hyprpaper),
+ * but some backends like swww work on any wayland compositor,
+ * so the backend is chosen separately from the desktop.
+ */
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Backend {
+    Hyprpaper,
+    Swaymsg,
+    Swww,
+    Gnome,
+    Cinnamon,
+    Mate,
+    Plasma,
+    Xwallpaper,
+    Feh,
+    Unknown,
+}
+
+impl Backend {
+    pub fn from(backend_name: &str) -> Self {
+        match backend_name.to_lowercase().as_str() {
+            "hyprpaper" => Backend::Hyprpaper,
+            "swaymsg" | "swaybg" => Backend::Swaymsg,
+            "swww" => Backend::Swww,
+            "gnome" => Backend::Gnome,
+            "cinnamon" => Backend::Cinnamon,
+            "mate" => Backend::Mate,
+            "plasma" | "kde" => Backend::Plasma,
+            "xwallpaper" => Backend::Xwallpaper,
+            "feh" => Backend::Feh,
+            _ => Backend::Unknown,
+        }
+    }
+
+    // Unset means Unknown, but a name that isn't a backend is an error
+    pub fn from_env(env_var: &str) -> Result<Self> {
+        match env::var(env_var) {
+            Ok(backend_name) => {
+                let backend = Backend::from(&backend_name);
+                ensure!(
+                    !backend.is_unknown(),
+                    "Unknown backend in {}: {}",
+                    env_var,
+                    backend_name
+                );
+                Ok(backend)
+            }
+            Err(_) => Ok(Backend::Unknown),
+        }
+    }
+
+    // swww is preferred whenever its daemon is running,
+    // otherwise the default backend of the desktop is used.
+    pub fn detect(desktop: &Desktop) -> Self {
+        if Self::is_swww_running() {
+            return Backend::Swww;
+        }
+
+        match desktop {
+            Desktop::Hyprland => Backend::Hyprpaper,
+            Desktop::Sway => Backend::Swaymsg,
+            Desktop::Gnome => Backend::Gnome,
+            Desktop::Cinnamon => Backend::Cinnamon,
+            Desktop::Mate => Backend::Mate,
+            Desktop::Kde => Backend::Plasma,
+            // xwallpaper can set a wallpaper per monitor, feh can't
+            desktop if desktop.is_x11() && Self::is_installed("xwallpaper") => Backend::Xwallpaper,
+            desktop if desktop.is_x11() && Self::is_installed("feh") => Backend::Feh,
+            _ => Backend::Unknown,
+        }
+    }
+
+    pub fn is_unknown(&self) -> bool {
+        matches!(*self, Backend::Unknown)
+    }
+
+    // `swww query` fails when it can't connect to the daemon
+    fn is_swww_running() -> bool {
+        Command::new("swww")
+            .arg("query")
+            .output()
+            .map(|output| output.status.success())
+            .unwrap_or(false)
+    }
+
+    fn is_installed(program: &str) -> bool {
+        env::var_os("PATH")
+            .map(|paths| {
+                env::split_paths(&paths).any(|dir| Path::new(&dir).join(program).is_file())
+            })
+            .unwrap_or(false)
+    }
+}
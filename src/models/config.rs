@@ -0,0 +1,338 @@
+use std::{collections::HashMap, path::PathBuf, str::FromStr};
+
+use anyhow::{Context, Result, anyhow, ensure};
+use ratatui::style::Color;
+use serde::Deserialize;
+
+use crate::models::{backend::Backend, image_format::ImageFormat, key_chord::KeyChord};
+
+/*
+ * Config is read from ~/.config/walrust/config.toml, e.g.
+ *
+ * [general]
+ * backend = "swww"
+ *
+ * [directories]
+ * default = "~/pictures/wallpapers"
+ * daemon = "~/pictures/slideshow"
+ *
+ * [tui]
+ * preview_width = 50
+ *
+ * Every section and option is optional, missing ones keep the defaults below.
+ * Unknown options are rejected so typos don't go unnoticed.
+ */
+
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct Config {
+    pub general: GeneralConfig,
+    pub directories: DirectoriesConfig,
+    pub images: ImagesConfig,
+    pub tui: TuiConfig,
+    pub keybindings: KeybindingsConfig,
+    pub theme: ThemeConfig,
+}
+
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct GeneralConfig {
+    pub backend: Option<String>, // Overridden by --backend and WALRUST_BACKEND
+}
+
+// Used when a command is run without a directory
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct DirectoriesConfig {
+    pub default: Option<PathBuf>,
+    pub pick: Option<PathBuf>,
+    pub random: Option<PathBuf>,
+    pub daemon: Option<PathBuf>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct ImagesConfig {
+    pub extensions: Vec<String>,
+    pub detect_by_content: bool, // Also recognize images by their first bytes
+    pub check_headers: bool,     // Flag the images whose header can't be decoded
+    pub recursive: bool,         // Also list the images in subdirectories
+    pub max_depth: usize,        // Levels of subdirectories below the directory
+    pub follow_symlinks: bool,   // Symlink loops are always skipped
+    pub hidden_directories: bool, // Scan directories starting with a dot
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct TuiConfig {
+    pub tick_rate_ms: u64, // How often the event listener polls the terminal
+    pub preview_cache_size: usize, // Number of image previews kept in memory
+    pub preview_width: u16, // Percentage of the width used by the preview
+}
+
+// Each action accepts a list of keys, e.g. ["j", "down", "ctrl+n"] (see KeyChord)
+#[derive(Debug, Clone, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct KeybindingsConfig {
+    pub quit: Vec<String>,
+    pub next_monitor: Vec<String>,
+    pub previous_monitor: Vec<String>,
+    pub next_image: Vec<String>,
+    pub previous_image: Vec<String>,
+    pub set_wallpaper: Vec<String>,
+    pub search: Vec<String>,
+    pub filter: Vec<String>,
+    pub next_match: Vec<String>,
+    pub previous_match: Vec<String>,
+    pub parent_directory: Vec<String>,
+}
+
+// Colors are names (e.g. "blue"), indexes (e.g. "12") or hex codes (e.g. "#89b4fa")
+#[derive(Debug, Clone, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct ThemeConfig {
+    pub border: String,
+    pub title: String,
+    pub selected: String, // The selected image in the list
+    pub matched: String,  // Characters that match the search or filter
+    pub corrupt: String,  // Images that can't be decoded
+    pub highlight_symbol: String,
+}
+
+// The extensions of every format walrust is built to decode
+impl Default for ImagesConfig {
+    fn default() -> Self {
+        ImagesConfig {
+            extensions: ImageFormat::ALL
+                .into_iter()
+                .filter(|format| format.is_decodable())
+                .flat_map(|format| format.extensions())
+                .map(|ext| ext.to_string())
+                .collect(),
+            detect_by_content: true,
+            check_headers: false,
+            recursive: false,
+            max_depth: 5,
+            follow_symlinks: false,
+            hidden_directories: false,
+        }
+    }
+}
+
+impl Default for TuiConfig {
+    fn default() -> Self {
+        TuiConfig {
+            tick_rate_ms: 250,
+            preview_cache_size: 8,
+            preview_width: 40,
+        }
+    }
+}
+
+impl Default for KeybindingsConfig {
+    fn default() -> Self {
+        let keys = |keys: &[&str]| keys.iter().map(|key| key.to_string()).collect();
+
+        KeybindingsConfig {
+            quit: keys(&["q", "esc", "ctrl+c"]),
+            next_monitor: keys(&["l", "right"]),
+            previous_monitor: keys(&["h", "left"]),
+            next_image: keys(&["j", "down"]),
+            previous_image: keys(&["k", "up"]),
+            set_wallpaper: keys(&["enter"]),
+            search: keys(&["/"]),
+            filter: keys(&["f"]),
+            next_match: keys(&["n"]),
+            previous_match: keys(&["N"]),
+            parent_directory: keys(&["backspace"]),
+        }
+    }
+}
+
+impl Default for ThemeConfig {
+    fn default() -> Self {
+        ThemeConfig {
+            border: String::from("reset"),
+            title: String::from("reset"),
+            selected: String::from("reset"),
+            matched: String::from("yellow"),
+            corrupt: String::from("red"),
+            highlight_symbol: String::from("> "),
+        }
+    }
+}
+
+impl Config {
+    pub fn validate(&self) -> Result<()> {
+        if let Some(backend) = &self.general.backend {
+            ensure!(
+                !Backend::from(backend).is_unknown(),
+                "general.backend: unknown backend {:?}",
+                backend
+            );
+        }
+
+        self.images.validate()?;
+        self.tui.validate()?;
+        self.keybindings.validate()?;
+        self.theme.validate()?;
+
+        Ok(())
+    }
+}
+
+impl DirectoriesConfig {
+    // The given directory, or the one configured for the command, or the default one
+    pub fn resolve(&self, dir: Option<PathBuf>, command_dir: &Option<PathBuf>) -> Option<PathBuf> {
+        dir.or_else(|| command_dir.clone())
+            .or_else(|| self.default.clone())
+    }
+
+    // Every configured directory with its option name
+    pub fn all(&self) -> Vec<(&str, &PathBuf)> {
+        [
+            ("default", &self.default),
+            ("pick", &self.pick),
+            ("random", &self.random),
+            ("daemon", &self.daemon),
+        ]
+        .into_iter()
+        .filter_map(|(name, dir)| Some((name, dir.as_ref()?)))
+        .collect()
+    }
+}
+
+impl ImagesConfig {
+    fn validate(&self) -> Result<()> {
+        ensure!(
+            !self.extensions.is_empty(),
+            "images.extensions: at least one extension is needed"
+        );
+
+        for extension in &self.extensions {
+            ensure!(
+                !extension.is_empty() && !extension.contains('.'),
+                "images.extensions: {:?} must be written without the dot, e.g. \"png\"",
+                extension
+            );
+        }
+
+        ensure!(
+            !self.recursive || self.max_depth > 0,
+            "images.max_depth: must be greater than zero when recursive is enabled"
+        );
+
+        Ok(())
+    }
+}
+
+impl TuiConfig {
+    fn validate(&self) -> Result<()> {
+        ensure!(
+            (10..=1000).contains(&self.tick_rate_ms),
+            "tui.tick_rate_ms: must be between 10 and 1000, got {}",
+            self.tick_rate_ms
+        );
+        ensure!(
+            self.preview_cache_size > 0,
+            "tui.preview_cache_size: must be greater than zero"
+        );
+        ensure!(
+            (10..=90).contains(&self.preview_width),
+            "tui.preview_width: must be between 10 and 90 percent, got {}",
+            self.preview_width
+        );
+
+        Ok(())
+    }
+}
+
+impl KeybindingsConfig {
+    fn actions(&self) -> [(&str, &[String]); 11] {
+        [
+            ("quit", &self.quit),
+            ("next_monitor", &self.next_monitor),
+            ("previous_monitor", &self.previous_monitor),
+            ("next_image", &self.next_image),
+            ("previous_image", &self.previous_image),
+            ("set_wallpaper", &self.set_wallpaper),
+            ("search", &self.search),
+            ("filter", &self.filter),
+            ("next_match", &self.next_match),
+            ("previous_match", &self.previous_match),
+            ("parent_directory", &self.parent_directory),
+        ]
+    }
+
+    // A key can only be bound to one action
+    fn validate(&self) -> Result<()> {
+        let mut bound: HashMap<KeyChord, (&str, &str)> = HashMap::new();
+
+        for (action, keys) in self.actions() {
+            ensure!(
+                !keys.is_empty(),
+                "keybindings.{}: at least one key is needed",
+                action
+            );
+
+            for key in keys {
+                let chord: KeyChord = key
+                    .parse()
+                    .with_context(|| format!("keybindings.{}", action))?;
+
+                if let Some((other_action, other_key)) = bound.insert(chord, (action, key)) {
+                    ensure!(
+                        other_action == action,
+                        "keybindings.{}: {:?} is already bound to {} as {:?}",
+                        action,
+                        key,
+                        other_action,
+                        other_key
+                    );
+                }
+            }
+        }
+
+        Ok(())
+    }
+}
+
+impl ThemeConfig {
+    pub fn border_color(&self) -> Color {
+        parse_color(&self.border).unwrap_or_default()
+    }
+
+    pub fn title_color(&self) -> Color {
+        parse_color(&self.title).unwrap_or_default()
+    }
+
+    pub fn selected_color(&self) -> Color {
+        parse_color(&self.selected).unwrap_or_default()
+    }
+
+    pub fn matched_color(&self) -> Color {
+        parse_color(&self.matched).unwrap_or_default()
+    }
+
+    pub fn corrupt_color(&self) -> Color {
+        parse_color(&self.corrupt).unwrap_or_default()
+    }
+
+    fn validate(&self) -> Result<()> {
+        for (name, color) in [
+            ("border", &self.border),
+            ("title", &self.title),
+            ("selected", &self.selected),
+            ("matched", &self.matched),
+            ("corrupt", &self.corrupt),
+        ] {
+            parse_color(color).with_context(|| format!("theme.{}", name))?;
+        }
+
+        Ok(())
+    }
+}
+
+fn parse_color(color: &str) -> Result<Color> {
+    Color::from_str(color).map_err(|_| anyhow!("Unknown color {:?}", color))
+}
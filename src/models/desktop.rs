@@ -2,6 +2,17 @@ use std::env;
 
 pub enum Desktop {
     Hyprland,
+    Sway,
+    Gnome,
+    Cinnamon,
+    Mate,
+    Kde,
+
+    // X11 window managers, they don't draw the wallpaper by themselves
+    I3,
+    Bspwm,
+    Awesome,
+    X11, // Any other X11 session
     Unknown,
 }
 
@@ -9,13 +20,31 @@ impl Desktop {
     pub fn from(wm_name: &str) -> Self {
         match wm_name {
             "Hyprland" => Desktop::Hyprland,
+            "sway" | "Sway" => Desktop::Sway,
+            "GNOME" | "gnome" | "GNOME-Classic" | "ubuntu" => Desktop::Gnome,
+            "X-Cinnamon" | "Cinnamon" | "cinnamon" => Desktop::Cinnamon,
+            "MATE" | "mate" => Desktop::Mate,
+            "KDE" | "kde" | "plasma" | "plasmawayland" | "plasmax11" => Desktop::Kde,
+            "i3" | "i3wm" => Desktop::I3,
+            "bspwm" => Desktop::Bspwm,
+            "awesome" => Desktop::Awesome,
             _ => Desktop::Unknown,
         }
     }
 
+    // XDG_CURRENT_DESKTOP may contain a colon separated list
+    // (e.g. "ubuntu:GNOME"), the first known name is used.
+    pub fn from_list(wm_names: &str) -> Self {
+        wm_names
+            .split(':')
+            .map(Desktop::from)
+            .find(|desktop| !desktop.is_unknown())
+            .unwrap_or(Desktop::Unknown)
+    }
+
     pub fn from_env(env_var: &str) -> Self {
         if let Ok(wm_name) = env::var(env_var) {
-            return Desktop::from(&wm_name);
+            return Desktop::from_list(&wm_name);
         }
         Desktop::Unknown
     }
@@ -36,10 +65,21 @@ impl Desktop {
             return desktop;
         }
 
+        if env::var("XDG_SESSION_TYPE").is_ok_and(|session_type| session_type == "x11") {
+            return Desktop::X11;
+        }
+
         Desktop::Unknown
     }
 
     pub fn is_unknown(&self) -> bool {
         matches!(*self, Desktop::Unknown)
     }
+
+    pub fn is_x11(&self) -> bool {
+        matches!(
+            *self,
+            Desktop::I3 | Desktop::Bspwm | Desktop::Awesome | Desktop::X11
+        )
+    }
 }
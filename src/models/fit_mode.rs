@@ -0,0 +1,45 @@
+use std::{env, str::FromStr};
+
+use anyhow::{Context, Result, anyhow};
+
+/*
+ * FitMode describes how an image is scaled to the monitor.
+ * Each backend maps it to its own option, backends that can't
+ * scale the image (e.g. hyprpaper) ignore it.
+ */
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum FitMode {
+    #[default]
+    Fill, // Scale and crop to cover the whole monitor
+    Fit,     // Scale to fit inside the monitor, keeping the aspect ratio
+    Stretch, // Scale to the monitor size, ignoring the aspect ratio
+    Center,  // Keep the original size
+    Tile,    // Repeat the image
+}
+
+impl FitMode {
+    pub fn from_env(env_var: &str) -> Result<Self> {
+        match env::var(env_var) {
+            Ok(value) => value
+                .parse()
+                .with_context(|| format!("Invalid value for {}", env_var)),
+            Err(_) => Ok(FitMode::default()),
+        }
+    }
+}
+
+impl FromStr for FitMode {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        match s.to_lowercase().as_str() {
+            "fill" => Ok(Self::Fill),
+            "fit" => Ok(Self::Fit),
+            "stretch" => Ok(Self::Stretch),
+            "center" => Ok(Self::Center),
+            "tile" => Ok(Self::Tile),
+            _ => Err(anyhow!("Unknown fit mode: {}", s)),
+        }
+    }
+}
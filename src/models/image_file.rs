@@ -1,25 +1,43 @@
 use std::{
+    borrow::Cow,
+    ffi::OsString,
     fmt::{self, Display},
     path::PathBuf,
 };
 
+use crate::models::image_format::ImageFormat;
+
+/*
+ * ImageFile is an image and its name in the list.
+ * The name is kept as the file system stores it, which isn't always UTF-8,
+ * display_name replaces the invalid bytes to show it.
+ */
+
 #[derive(Debug, Clone)]
 pub struct ImageFile {
-    pub name: String,
+    pub name: OsString,
     pub path: PathBuf,
+    pub format: Option<ImageFormat>,
+    pub corrupt: bool, // The image can't be decoded
 }
 
 impl ImageFile {
-    pub fn new(name: impl Into<String>, path: impl Into<PathBuf>) -> Self {
+    pub fn new(name: impl Into<OsString>, path: impl Into<PathBuf>) -> Self {
         ImageFile {
             name: name.into(),
             path: path.into(),
+            format: None,
+            corrupt: false,
         }
     }
+
+    pub fn display_name(&self) -> Cow<'_, str> {
+        self.name.to_string_lossy()
+    }
 }
 
 impl Display for ImageFile {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
-        write!(f, "({})", self.name)
+        write!(f, "({})", self.display_name())
     }
 }
@@ -0,0 +1,125 @@
+use std::{
+    fmt::{self, Display},
+    path::Path,
+};
+
+/*
+ * ImageFormat is a format walrust can list and hand to a backend.
+ * Decoding the optional formats, for previews and conversions,
+ * depends on the cargo features walrust is built with.
+ */
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum ImageFormat {
+    Png,
+    Jpeg,
+    Webp,
+    Gif,
+    Bmp,
+    Tiff,
+    Avif,
+    Jxl,
+    Qoi,
+}
+
+impl ImageFormat {
+    pub const ALL: [ImageFormat; 9] = [
+        ImageFormat::Png,
+        ImageFormat::Jpeg,
+        ImageFormat::Webp,
+        ImageFormat::Gif,
+        ImageFormat::Bmp,
+        ImageFormat::Tiff,
+        ImageFormat::Avif,
+        ImageFormat::Jxl,
+        ImageFormat::Qoi,
+    ];
+
+    pub fn extensions(self) -> &'static [&'static str] {
+        match self {
+            ImageFormat::Png => &["png"],
+            ImageFormat::Jpeg => &["jpg", "jpeg"],
+            ImageFormat::Webp => &["webp"],
+            ImageFormat::Gif => &["gif"],
+            ImageFormat::Bmp => &["bmp"],
+            ImageFormat::Tiff => &["tif", "tiff"],
+            ImageFormat::Avif => &["avif"],
+            ImageFormat::Jxl => &["jxl"],
+            ImageFormat::Qoi => &["qoi"],
+        }
+    }
+
+    pub fn from_extension(extension: &str) -> Option<Self> {
+        Self::ALL.into_iter().find(|format| {
+            format
+                .extensions()
+                .iter()
+                .any(|known| known.eq_ignore_ascii_case(extension))
+        })
+    }
+
+    pub fn from_path(path: &Path) -> Option<Self> {
+        path.extension()
+            .and_then(|ext| ext.to_str())
+            .and_then(Self::from_extension)
+    }
+
+    // Recognizes the format from the first 16 bytes of a file
+    pub fn from_magic(bytes: &[u8]) -> Option<Self> {
+        let at =
+            |offset: usize, magic: &[u8]| bytes.get(offset..).is_some_and(|b| b.starts_with(magic));
+
+        if at(0, b"\x89PNG\r\n\x1a\n") {
+            Some(ImageFormat::Png)
+        } else if at(0, b"\xff\xd8\xff") {
+            Some(ImageFormat::Jpeg)
+        } else if at(0, b"RIFF") && at(8, b"WEBP") {
+            Some(ImageFormat::Webp)
+        } else if at(0, b"GIF87a") || at(0, b"GIF89a") {
+            Some(ImageFormat::Gif)
+        } else if at(0, b"BM") {
+            Some(ImageFormat::Bmp)
+        } else if at(0, b"II*\0") || at(0, b"MM\0*") {
+            Some(ImageFormat::Tiff)
+        } else if at(4, b"ftypavif") || at(4, b"ftypavis") {
+            Some(ImageFormat::Avif)
+        } else if at(0, b"\xff\x0a") || at(0, b"\0\0\0\x0cJXL \r\n\x87\n") {
+            Some(ImageFormat::Jxl)
+        } else if at(0, b"qoif") {
+            Some(ImageFormat::Qoi)
+        } else {
+            None
+        }
+    }
+
+    // PNG, JPEG and WebP are always built in, the others are cargo features
+    pub fn is_decodable(self) -> bool {
+        match self {
+            ImageFormat::Png | ImageFormat::Jpeg | ImageFormat::Webp => true,
+            ImageFormat::Gif => cfg!(feature = "gif"),
+            ImageFormat::Bmp => cfg!(feature = "bmp"),
+            ImageFormat::Tiff => cfg!(feature = "tiff"),
+            ImageFormat::Avif => cfg!(feature = "avif"),
+            ImageFormat::Jxl => cfg!(feature = "jxl"),
+            ImageFormat::Qoi => cfg!(feature = "qoi"),
+        }
+    }
+}
+
+impl Display for ImageFormat {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let name = match self {
+            ImageFormat::Png => "PNG",
+            ImageFormat::Jpeg => "JPEG",
+            ImageFormat::Webp => "WebP",
+            ImageFormat::Gif => "GIF",
+            ImageFormat::Bmp => "BMP",
+            ImageFormat::Tiff => "TIFF",
+            ImageFormat::Avif => "AVIF",
+            ImageFormat::Jxl => "JPEG XL",
+            ImageFormat::Qoi => "QOI",
+        };
+
+        write!(f, "{}", name)
+    }
+}
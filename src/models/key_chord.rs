@@ -0,0 +1,87 @@
+use std::str::FromStr;
+
+use anyhow::{Result, anyhow};
+use ratatui::crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
+
+/*
+ * KeyChord is a key with the Ctrl and Alt modifiers held, written like
+ * "q", "enter", "ctrl+n" or "ctrl+alt+left".
+ * Shift is part of the character instead (e.g. "Q"), and shift+tab is "backtab",
+ * because that's how terminals report them.
+ */
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct KeyChord {
+    pub code: KeyCode,
+    pub modifiers: KeyModifiers,
+}
+
+impl From<KeyEvent> for KeyChord {
+    fn from(event: KeyEvent) -> Self {
+        KeyChord {
+            code: event.code,
+            modifiers: event.modifiers & (KeyModifiers::CONTROL | KeyModifiers::ALT),
+        }
+    }
+}
+
+impl FromStr for KeyChord {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        // "+" and "ctrl++" bind the plus key itself
+        let (modifier_names, key) = match s.strip_suffix("++") {
+            Some(modifier_names) => (modifier_names, "+"),
+            None => match s.rsplit_once('+') {
+                Some((modifier_names, key)) if !key.is_empty() => (modifier_names, key),
+                _ => ("", s),
+            },
+        };
+
+        let mut modifiers = KeyModifiers::NONE;
+        for name in modifier_names.split('+').filter(|name| !name.is_empty()) {
+            modifiers |= match name.to_lowercase().as_str() {
+                "ctrl" | "control" => KeyModifiers::CONTROL,
+                "alt" => KeyModifiers::ALT,
+                _ => return Err(anyhow!("Unknown modifier {:?} in {:?}", name, s)),
+            };
+        }
+
+        Ok(KeyChord {
+            code: parse_key(key).map_err(|_| anyhow!("Unknown key {:?}", s))?,
+            modifiers,
+        })
+    }
+}
+
+// A single character, or the name of a special key
+fn parse_key(key: &str) -> Result<KeyCode> {
+    let mut chars = key.chars();
+    if let (Some(char), None) = (chars.next(), chars.next()) {
+        return Ok(KeyCode::Char(char));
+    }
+
+    match key.to_lowercase().as_str() {
+        "enter" => Ok(KeyCode::Enter),
+        "esc" => Ok(KeyCode::Esc),
+        "space" => Ok(KeyCode::Char(' ')),
+        "tab" => Ok(KeyCode::Tab),
+        "backtab" => Ok(KeyCode::BackTab),
+        "backspace" => Ok(KeyCode::Backspace),
+        "delete" => Ok(KeyCode::Delete),
+        "up" => Ok(KeyCode::Up),
+        "down" => Ok(KeyCode::Down),
+        "left" => Ok(KeyCode::Left),
+        "right" => Ok(KeyCode::Right),
+        "home" => Ok(KeyCode::Home),
+        "end" => Ok(KeyCode::End),
+        "pageup" => Ok(KeyCode::PageUp),
+        "pagedown" => Ok(KeyCode::PageDown),
+        name => name
+            .strip_prefix('f')
+            .and_then(|number| number.parse().ok())
+            .filter(|number| (1..=12).contains(number))
+            .map(KeyCode::F)
+            .ok_or(anyhow!("Unknown key {:?}", key)),
+    }
+}
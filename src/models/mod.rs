@@ -1,2 +1,14 @@
+pub mod backend;
+pub mod config;
 pub mod desktop;
+pub mod fit_mode;
 pub mod image_file;
+pub mod image_format;
+pub mod key_chord;
+pub mod monitor;
+pub mod os_path;
+pub mod schedule;
+pub mod slideshow;
+pub mod solar;
+pub mod timetable;
+pub mod wallpaper_state;
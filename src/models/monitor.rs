@@ -0,0 +1,144 @@
+use std::fmt::{self, Display};
+
+// Prefix of a selector that targets a monitor by its description,
+// the same syntax Hyprland and Hyprpaper use.
+pub const DESCRIPTION_SELECTOR_PREFIX: &str = "desc:";
+
+// Position and size of a monitor in the layout, in pixels
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct MonitorGeometry {
+    pub width: u32,
+    pub height: u32,
+    pub x: i32,
+    pub y: i32,
+    pub scale: f64,
+    pub transform: u8, // Same values as Hyprland: 0-3 rotations, 4-7 flipped rotations
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Monitor {
+    pub name: String,                // Connector name, e.g. "DP-1"
+    pub description: Option<String>, // Usually make, model and serial
+    pub geometry: Option<MonitorGeometry>,
+    pub focused: bool,
+    pub shared_description: bool, // Another connected monitor has the same description
+}
+
+impl Monitor {
+    pub fn new(name: impl Into<String>) -> Self {
+        Monitor {
+            name: name.into(),
+            description: None,
+            geometry: None,
+            focused: false,
+            shared_description: false,
+        }
+    }
+
+    // Identical monitors without a serial number have the same description,
+    // so their selectors have to fall back to the connector name.
+    pub fn mark_shared_descriptions(monitors: &mut [Monitor]) {
+        let descriptions: Vec<Option<String>> = monitors
+            .iter()
+            .map(|monitor| monitor.description.clone())
+            .collect();
+
+        for monitor in monitors.iter_mut() {
+            monitor.shared_description = monitor.description.is_some()
+                && descriptions
+                    .iter()
+                    .filter(|description| **description == monitor.description)
+                    .count()
+                    > 1;
+        }
+    }
+
+    pub fn with_description(mut self, description: impl Into<String>) -> Self {
+        let description = description.into();
+        self.description = (!description.is_empty()).then_some(description);
+        self
+    }
+
+    pub fn with_geometry(mut self, geometry: MonitorGeometry) -> Self {
+        self.geometry = Some(geometry);
+        self
+    }
+
+    pub fn with_focused(mut self, focused: bool) -> Self {
+        self.focused = focused;
+        self
+    }
+
+    // Connector names (DP-1, DP-2...) can change between docks,
+    // the description stays with the physical screen.
+    // This should be used whenever a monitor is saved.
+    pub fn selector(&self) -> String {
+        match &self.description {
+            Some(description) if !self.shared_description => {
+                format!("{}{}", DESCRIPTION_SELECTOR_PREFIX, description)
+            }
+            _ => self.name.clone(),
+        }
+    }
+
+    // Matches a connector name (e.g. "DP-1"),
+    // or the start of the description (e.g. "desc:Dell Inc. DELL U2720Q").
+    pub fn matches(&self, selector: &str) -> bool {
+        let selector = selector.trim();
+
+        match selector.strip_prefix(DESCRIPTION_SELECTOR_PREFIX) {
+            Some(description) => self.description.as_ref().is_some_and(|d| {
+                !description.trim().is_empty() && d.starts_with(description.trim())
+            }),
+            None => self.name == selector,
+        }
+    }
+}
+
+// e.g. "DP-1 — 2560x1440@1.25, focused"
+impl Display for Monitor {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", self.name)?;
+
+        if let Some(geometry) = &self.geometry {
+            write!(
+                f,
+                " — {}x{}@{}",
+                geometry.width, geometry.height, geometry.scale
+            )?;
+        }
+
+        if self.focused {
+            write!(f, ", focused")?;
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn shared_descriptions_fall_back_to_the_connector() {
+        let mut monitors = vec![
+            Monitor::new("DP-1").with_description("Dell Inc. DELL P2419H"),
+            Monitor::new("DP-2").with_description("Dell Inc. DELL P2419H"),
+            Monitor::new("HDMI-A-1").with_description("LG Electronics 27GL850 0x0001"),
+            Monitor::new("eDP-1"),
+        ];
+        Monitor::mark_shared_descriptions(&mut monitors);
+
+        let selectors: Vec<String> = monitors.iter().map(Monitor::selector).collect();
+        assert_eq!(
+            selectors,
+            [
+                "DP-1",
+                "DP-2",
+                "desc:LG Electronics 27GL850 0x0001",
+                "eDP-1"
+            ]
+        );
+    }
+}
@@ -0,0 +1,175 @@
+use std::{
+    collections::{BTreeMap, VecDeque},
+    ffi::OsString,
+    os::unix::ffi::{OsStrExt, OsStringExt},
+    path::{Path, PathBuf},
+};
+
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+
+/*
+ * Serde can only write UTF-8 paths as strings, so these helpers write
+ * the other paths as arrays of bytes to keep them intact, e.g.
+ *
+ * #[serde(with = "os_path")]
+ * pub path: PathBuf,
+ *
+ * The submodules do the same for the collections of paths.
+ */
+
+#[derive(Serialize, Deserialize)]
+#[serde(untagged)]
+enum OsPath {
+    Utf8(String),
+    Bytes(Vec<u8>),
+}
+
+impl From<&Path> for OsPath {
+    fn from(path: &Path) -> Self {
+        match path.to_str() {
+            Some(path) => OsPath::Utf8(path.to_owned()),
+            None => OsPath::Bytes(path.as_os_str().as_bytes().to_vec()),
+        }
+    }
+}
+
+impl From<OsPath> for PathBuf {
+    fn from(path: OsPath) -> Self {
+        match path {
+            OsPath::Utf8(path) => PathBuf::from(path),
+            OsPath::Bytes(bytes) => PathBuf::from(OsString::from_vec(bytes)),
+        }
+    }
+}
+
+pub fn serialize<S: Serializer>(path: &Path, serializer: S) -> Result<S::Ok, S::Error> {
+    OsPath::from(path).serialize(serializer)
+}
+
+pub fn deserialize<'de, D: Deserializer<'de>>(deserializer: D) -> Result<PathBuf, D::Error> {
+    OsPath::deserialize(deserializer).map(PathBuf::from)
+}
+
+pub mod option {
+    use super::*;
+
+    pub fn serialize<S: Serializer>(
+        path: &Option<PathBuf>,
+        serializer: S,
+    ) -> Result<S::Ok, S::Error> {
+        path.as_deref().map(OsPath::from).serialize(serializer)
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(
+        deserializer: D,
+    ) -> Result<Option<PathBuf>, D::Error> {
+        Ok(Option::<OsPath>::deserialize(deserializer)?.map(PathBuf::from))
+    }
+}
+
+pub mod list {
+    use super::*;
+
+    pub fn serialize<S: Serializer>(
+        paths: &VecDeque<PathBuf>,
+        serializer: S,
+    ) -> Result<S::Ok, S::Error> {
+        serializer.collect_seq(paths.iter().map(|path| OsPath::from(path.as_path())))
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(
+        deserializer: D,
+    ) -> Result<VecDeque<PathBuf>, D::Error> {
+        let paths = Vec::<OsPath>::deserialize(deserializer)?;
+
+        Ok(paths.into_iter().map(PathBuf::from).collect())
+    }
+}
+
+pub mod map {
+    use super::*;
+
+    pub fn serialize<S: Serializer>(
+        paths: &BTreeMap<String, PathBuf>,
+        serializer: S,
+    ) -> Result<S::Ok, S::Error> {
+        serializer.collect_map(
+            paths
+                .iter()
+                .map(|(key, path)| (key, OsPath::from(path.as_path()))),
+        )
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(
+        deserializer: D,
+    ) -> Result<BTreeMap<String, PathBuf>, D::Error> {
+        let paths = BTreeMap::<String, OsPath>::deserialize(deserializer)?;
+
+        Ok(paths
+            .into_iter()
+            .map(|(key, path)| (key, PathBuf::from(path)))
+            .collect())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::*;
+
+    #[derive(Debug, PartialEq, Serialize, Deserialize)]
+    struct Paths {
+        #[serde(with = "super")]
+        path: PathBuf,
+        #[serde(with = "option")]
+        option: Option<PathBuf>,
+        #[serde(with = "list")]
+        list: VecDeque<PathBuf>,
+        #[serde(with = "map")]
+        map: BTreeMap<String, PathBuf>,
+    }
+
+    fn non_utf8(bytes: &[u8]) -> PathBuf {
+        PathBuf::from(OsString::from_vec(bytes.to_vec()))
+    }
+
+    #[test]
+    fn utf8_paths_are_strings() {
+        let paths = Paths {
+            path: PathBuf::from("/images/café.png"),
+            option: None,
+            list: VecDeque::from([PathBuf::from("/a.png")]),
+            map: BTreeMap::from([(String::from("DP-1"), PathBuf::from("/b.png"))]),
+        };
+
+        assert_eq!(
+            serde_json::to_value(&paths).unwrap(),
+            json!({
+                "path": "/images/café.png",
+                "option": null,
+                "list": ["/a.png"],
+                "map": {"DP-1": "/b.png"},
+            })
+        );
+    }
+
+    #[test]
+    fn non_utf8_paths_round_trip() {
+        let paths = Paths {
+            path: non_utf8(b"/images/caf\xe9.png"),
+            option: Some(non_utf8(b"/\xff")),
+            list: VecDeque::from([PathBuf::from("/a.png"), non_utf8(b"/\xfe.jpg")]),
+            map: BTreeMap::from([
+                (String::from("DP-1"), non_utf8(b"/sub\xff/b.png")),
+                (String::from("DP-2"), PathBuf::from("/c.png")),
+            ]),
+        };
+
+        let json = serde_json::to_string(&paths).unwrap();
+        let decoded: Paths = serde_json::from_str(&json).unwrap();
+
+        assert_eq!(decoded, paths);
+        assert_eq!(decoded.path.as_os_str().as_bytes(), b"/images/caf\xe9.png");
+    }
+}
@@ -0,0 +1,400 @@
+use std::{
+    fmt::{self, Display},
+    path::PathBuf,
+    str::FromStr,
+};
+
+use anyhow::{Context, Result, anyhow, ensure};
+use chrono::{NaiveDate, NaiveDateTime, NaiveTime, TimeDelta};
+use serde::Deserialize;
+
+use crate::models::solar::{Location, SunTimes};
+
+/*
+ * Schedule binds images or directories to times of the day, e.g.
+ *
+ * [location]
+ * latitude = 52.37
+ * longitude = 4.89
+ *
+ * [[entry]]
+ * start = "sunrise"
+ * end = "12:00"
+ * path = "~/pictures/morning"
+ *
+ * [[entry]]
+ * start = "sunset-30m"
+ * path = "~/pictures/night.jpg"
+ *
+ * An entry without an end lasts until the next entry starts.
+ * When entries overlap, the one that started last wins.
+ */
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct Schedule {
+    pub location: Option<Location>, // Needed for sunrise and sunset
+
+    #[serde(rename = "entry", default)]
+    pub entries: Vec<ScheduleEntry>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct ScheduleEntry {
+    pub start: TimeSpec,
+    pub end: Option<TimeSpec>,
+    pub path: PathBuf, // An image, or a directory to choose a random image from
+}
+
+// A time of the day, either on the clock or relative to the sun
+#[derive(Debug, Clone, Copy, Deserialize)]
+#[serde(try_from = "String")]
+pub struct TimeSpec {
+    pub anchor: TimeAnchor,
+    pub offset: TimeDelta,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TimeAnchor {
+    Clock(NaiveTime),
+    Sunrise,
+    Sunset,
+}
+
+// The time range of an entry on a specific day
+#[derive(Debug, Clone, Copy)]
+pub struct ScheduleSlot {
+    pub start: NaiveTime,
+    pub end: NaiveTime, // Before start when the slot goes past midnight
+    pub entry_index: usize,
+}
+
+impl Schedule {
+    pub fn validate(&self) -> Result<()> {
+        ensure!(!self.entries.is_empty(), "The schedule has no entries");
+
+        let uses_sun = self
+            .entries
+            .iter()
+            .flat_map(|entry| [Some(entry.start), entry.end])
+            .flatten()
+            .any(|time| time.is_solar());
+        ensure!(
+            !uses_sun || self.location.is_some(),
+            "sunrise and sunset need a [location] with latitude and longitude"
+        );
+
+        if let Some(location) = self.location {
+            ensure!(
+                (-90.0..=90.0).contains(&location.latitude),
+                "Latitude must be between -90 and 90"
+            );
+            ensure!(
+                (-180.0..=180.0).contains(&location.longitude),
+                "Longitude must be between -180 and 180"
+            );
+        }
+
+        Ok(())
+    }
+
+    pub fn sun_times(&self, date: NaiveDate) -> Option<SunTimes> {
+        self.location?.sun_times(date)
+    }
+
+    // The slots of the day sorted by start.
+    // Entries relative to a sun that doesn't rise or set that day are left out.
+    pub fn timeline(&self, date: NaiveDate) -> Vec<ScheduleSlot> {
+        let sun_times = self.sun_times(date);
+
+        let mut starts: Vec<(NaiveTime, usize)> = self
+            .entries
+            .iter()
+            .enumerate()
+            .filter_map(|(index, entry)| Some((entry.start.resolve(sun_times)?, index)))
+            .collect();
+        starts.sort();
+
+        (0..starts.len())
+            .filter_map(|i| {
+                let (start, entry_index) = starts[i];
+                let end = match self.entries[entry_index].end {
+                    Some(end) => end.resolve(sun_times)?,
+                    None => starts[(i + 1) % starts.len()].0,
+                };
+
+                Some(ScheduleSlot {
+                    start,
+                    end,
+                    entry_index,
+                })
+            })
+            .collect()
+    }
+
+    // The entry that should be shown at that time, if any
+    pub fn active(&self, at: NaiveDateTime) -> Option<usize> {
+        let time = at.time();
+
+        self.timeline(at.date())
+            .into_iter()
+            .filter(|slot| slot.contains(time))
+            .min_by_key(|slot| since(slot.start, time))
+            .map(|slot| slot.entry_index)
+    }
+
+    // The next time the active entry may change, at the latest the next midnight
+    pub fn next_change(&self, after: NaiveDateTime) -> NaiveDateTime {
+        let midnight = (after.date() + TimeDelta::days(1)).and_time(NaiveTime::MIN);
+
+        self.timeline(after.date())
+            .into_iter()
+            .flat_map(|slot| [slot.start, slot.end])
+            .filter(|time| *time > after.time())
+            .min()
+            .map(|time| after.date().and_time(time))
+            .unwrap_or(midnight)
+    }
+}
+
+impl ScheduleSlot {
+    pub fn contains(&self, time: NaiveTime) -> bool {
+        if self.start == self.end {
+            return true; // The whole day
+        }
+
+        if self.start < self.end {
+            self.start <= time && time < self.end
+        } else {
+            self.start <= time || time < self.end
+        }
+    }
+}
+
+impl TimeSpec {
+    pub fn is_solar(&self) -> bool {
+        matches!(self.anchor, TimeAnchor::Sunrise | TimeAnchor::Sunset)
+    }
+
+    pub fn resolve(&self, sun_times: Option<SunTimes>) -> Option<NaiveTime> {
+        let time = match self.anchor {
+            TimeAnchor::Clock(time) => time,
+            TimeAnchor::Sunrise => sun_times?.sunrise,
+            TimeAnchor::Sunset => sun_times?.sunset,
+        };
+
+        Some(time + self.offset)
+    }
+}
+
+// Time from `start` to `time`, going past midnight if needed
+fn since(start: NaiveTime, time: NaiveTime) -> TimeDelta {
+    let delta = time - start;
+    if delta < TimeDelta::zero() {
+        delta + TimeDelta::days(1)
+    } else {
+        delta
+    }
+}
+
+// Accepts HH:MM, sunrise or sunset, the latter with an offset like +30m or -1h
+impl FromStr for TimeSpec {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        let s = s.trim().to_lowercase();
+
+        for (name, anchor) in [
+            ("sunrise", TimeAnchor::Sunrise),
+            ("sunset", TimeAnchor::Sunset),
+        ] {
+            if let Some(offset) = s.strip_prefix(name) {
+                return Ok(TimeSpec {
+                    anchor,
+                    offset: parse_offset(offset).with_context(|| format!("Invalid time: {}", s))?,
+                });
+            }
+        }
+
+        let time = NaiveTime::parse_from_str(&s, "%H:%M")
+            .with_context(|| format!("Invalid time: {}, expected HH:MM, sunrise or sunset", s))?;
+
+        Ok(TimeSpec {
+            anchor: TimeAnchor::Clock(time),
+            offset: TimeDelta::zero(),
+        })
+    }
+}
+
+impl TryFrom<String> for TimeSpec {
+    type Error = anyhow::Error;
+
+    fn try_from(value: String) -> Result<Self> {
+        value.parse()
+    }
+}
+
+// e.g. "", "+30m", "-1h" or "+90", minutes by default
+fn parse_offset(offset: &str) -> Result<TimeDelta> {
+    let offset = offset.trim();
+    if offset.is_empty() {
+        return Ok(TimeDelta::zero());
+    }
+
+    let (sign, rest) = if let Some(rest) = offset.strip_prefix('+') {
+        (1, rest)
+    } else if let Some(rest) = offset.strip_prefix('-') {
+        (-1, rest)
+    } else {
+        return Err(anyhow!("Offset must start with + or -"));
+    };
+
+    let (number, minutes) = match rest.strip_suffix('h') {
+        Some(number) => (number, 60),
+        None => (rest.strip_suffix('m').unwrap_or(rest), 1),
+    };
+    let number: i64 = number.trim().parse().context("Invalid offset")?;
+
+    number
+        .checked_mul(sign * minutes)
+        .and_then(TimeDelta::try_minutes)
+        .ok_or(anyhow!("Offset is too large: {}", offset))
+}
+
+impl Display for TimeSpec {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self.anchor {
+            TimeAnchor::Clock(time) => write!(f, "{}", time.format("%H:%M"))?,
+            TimeAnchor::Sunrise => write!(f, "sunrise")?,
+            TimeAnchor::Sunset => write!(f, "sunset")?,
+        }
+
+        let minutes = self.offset.num_minutes();
+        match minutes {
+            0 => Ok(()),
+            _ if minutes % 60 == 0 => write!(f, "{:+}h", minutes / 60),
+            _ => write!(f, "{:+}m", minutes),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn time(hour: u32, minute: u32) -> NaiveTime {
+        NaiveTime::from_hms_opt(hour, minute, 0).unwrap()
+    }
+
+    fn at(hour: u32, minute: u32) -> NaiveDateTime {
+        NaiveDate::from_ymd_opt(2024, 3, 1)
+            .unwrap()
+            .and_time(time(hour, minute))
+    }
+
+    fn schedule(toml: &str) -> Schedule {
+        toml::from_str(toml).unwrap()
+    }
+
+    #[test]
+    fn time_specs_parse() {
+        let spec: TimeSpec = "07:30".parse().unwrap();
+        assert_eq!(spec.anchor, TimeAnchor::Clock(time(7, 30)));
+        assert_eq!(spec.offset, TimeDelta::zero());
+
+        let spec: TimeSpec = " Sunset-30m ".parse().unwrap();
+        assert_eq!(spec.anchor, TimeAnchor::Sunset);
+        assert_eq!(spec.offset, TimeDelta::minutes(-30));
+
+        for (input, minutes) in [("sunrise", 0), ("sunrise+1h", 60), ("sunrise + 90", 90)] {
+            let spec: TimeSpec = input.parse().unwrap();
+            assert_eq!(spec.anchor, TimeAnchor::Sunrise);
+            assert_eq!(spec.offset, TimeDelta::minutes(minutes), "{}", input);
+        }
+
+        for input in [
+            "25:00",
+            "7",
+            "noon",
+            "sunrise30m",
+            "sunset+1d",
+            "sunset+99999999999999h",
+        ] {
+            assert!(input.parse::<TimeSpec>().is_err(), "{}", input);
+        }
+    }
+
+    #[test]
+    fn time_specs_display_as_parsed() {
+        for input in ["07:30", "sunrise", "sunset-30m", "sunrise+2h"] {
+            assert_eq!(input.parse::<TimeSpec>().unwrap().to_string(), input);
+        }
+    }
+
+    #[test]
+    fn overnight_slots_contain_both_sides_of_midnight() {
+        let slot = ScheduleSlot {
+            start: time(22, 0),
+            end: time(6, 0),
+            entry_index: 0,
+        };
+
+        assert!(slot.contains(time(22, 0)));
+        assert!(slot.contains(time(23, 59)));
+        assert!(slot.contains(time(0, 0)));
+        assert!(slot.contains(time(5, 59)));
+        assert!(!slot.contains(time(6, 0)));
+        assert!(!slot.contains(time(12, 0)));
+    }
+
+    #[test]
+    fn active_entry_follows_the_timeline() {
+        let schedule = schedule(
+            r#"
+            [[entry]]
+            start = "08:00"
+            path = "day"
+
+            [[entry]]
+            start = "20:00"
+            path = "evening"
+
+            [[entry]]
+            start = "12:00"
+            end = "13:00"
+            path = "lunch"
+            "#,
+        );
+
+        assert_eq!(schedule.active(at(7, 0)), Some(1)); // Since yesterday evening
+        assert_eq!(schedule.active(at(8, 0)), Some(0));
+        assert_eq!(schedule.active(at(12, 30)), Some(2));
+        assert_eq!(schedule.active(at(13, 0)), None); // "day" ended when "lunch" started
+        assert_eq!(schedule.active(at(23, 0)), Some(1));
+    }
+
+    #[test]
+    fn next_change_is_the_next_boundary_or_midnight() {
+        let schedule = schedule(
+            r#"
+            [[entry]]
+            start = "08:00"
+            end = "12:00"
+            path = "morning"
+
+            [[entry]]
+            start = "20:00"
+            path = "night"
+            "#,
+        );
+
+        assert_eq!(schedule.next_change(at(7, 0)), at(8, 0));
+        assert_eq!(schedule.next_change(at(8, 0)), at(12, 0));
+        assert_eq!(schedule.next_change(at(12, 0)), at(20, 0));
+        assert_eq!(
+            schedule.next_change(at(21, 0)),
+            NaiveDate::from_ymd_opt(2024, 3, 2)
+                .unwrap()
+                .and_time(NaiveTime::MIN)
+        );
+    }
+}
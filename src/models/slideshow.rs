@@ -0,0 +1,75 @@
+use std::path::PathBuf;
+
+use chrono::{NaiveDate, NaiveDateTime, NaiveTime, TimeDelta};
+
+/*
+ * Slideshow is a cycle of images, each shown for a fixed duration,
+ * that started at a point in time and repeats forever,
+ * like the GNOME timed wallpaper XML files.
+ */
+
+#[derive(Debug, Clone)]
+pub struct Slideshow {
+    pub start: NaiveDateTime,
+    pub slides: Vec<Slide>,
+}
+
+#[derive(Debug, Clone)]
+pub struct Slide {
+    pub path: PathBuf,
+    pub duration: TimeDelta,
+}
+
+impl Slideshow {
+    fn period(&self) -> TimeDelta {
+        self.slides.iter().map(|slide| slide.duration).sum()
+    }
+
+    // The slide shown at that time and when it started
+    fn position(&self, at: NaiveDateTime) -> Option<(usize, NaiveDateTime)> {
+        let period = self.period().num_milliseconds();
+        if period <= 0 {
+            return None;
+        }
+
+        let offset = (at - self.start).num_milliseconds().rem_euclid(period);
+        let mut slide_start = at - TimeDelta::milliseconds(offset);
+
+        for (index, slide) in self.slides.iter().enumerate() {
+            if at < slide_start + slide.duration {
+                return Some((index, slide_start));
+            }
+            slide_start += slide.duration;
+        }
+
+        None
+    }
+
+    pub fn active(&self, at: NaiveDateTime) -> Option<usize> {
+        self.position(at).map(|(index, _)| index)
+    }
+
+    pub fn next_change(&self, after: NaiveDateTime) -> NaiveDateTime {
+        match self.position(after) {
+            Some((index, slide_start)) => slide_start + self.slides[index].duration,
+            None => (after.date() + TimeDelta::days(1)).and_time(NaiveTime::MIN),
+        }
+    }
+
+    // Every slide change of the day, starting with the slide shown at midnight
+    pub fn timeline(&self, date: NaiveDate) -> Vec<(NaiveDateTime, usize)> {
+        let mut time = date.and_time(NaiveTime::MIN);
+        let end = time + TimeDelta::days(1);
+        let mut changes = Vec::new();
+
+        while time < end {
+            let Some(index) = self.active(time) else {
+                break;
+            };
+            changes.push((time, index));
+            time = self.next_change(time);
+        }
+
+        changes
+    }
+}
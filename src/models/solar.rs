@@ -0,0 +1,124 @@
+use std::f64::consts::PI;
+
+use chrono::{Datelike, Local, NaiveDate, NaiveTime, TimeDelta, TimeZone, Utc};
+use serde::Deserialize;
+
+// The sun is below the horizon, including refraction and the size of the sun
+const ZENITH_DEGREES: f64 = 90.833;
+
+#[derive(Debug, Clone, Copy, Deserialize)]
+pub struct Location {
+    pub latitude: f64,
+    pub longitude: f64,
+}
+
+#[derive(Debug, Clone, Copy)]
+pub struct SunTimes {
+    pub sunrise: NaiveTime,
+    pub sunset: NaiveTime,
+}
+
+/*
+ * Computes sunrise and sunset offline with the NOAA approximation,
+ * which is accurate to about a minute away from the poles.
+ * The times are converted to the local timezone.
+ */
+
+impl Location {
+    // None when the sun doesn't rise or set on that day (polar day or night)
+    pub fn sun_times(&self, date: NaiveDate) -> Option<SunTimes> {
+        let gamma = 2.0 * PI / 365.0 * date.ordinal0() as f64;
+
+        // Minutes the solar noon differs from the clock noon
+        let equation_of_time = 229.18
+            * (0.000075 + 0.001868 * gamma.cos()
+                - 0.032077 * gamma.sin()
+                - 0.014615 * (2.0 * gamma).cos()
+                - 0.040849 * (2.0 * gamma).sin());
+
+        let declination = 0.006918 - 0.399912 * gamma.cos() + 0.070257 * gamma.sin()
+            - 0.006758 * (2.0 * gamma).cos()
+            + 0.000907 * (2.0 * gamma).sin()
+            - 0.002697 * (3.0 * gamma).cos()
+            + 0.00148 * (3.0 * gamma).sin();
+
+        let latitude = self.latitude.to_radians();
+        let cos_hour_angle = ZENITH_DEGREES.to_radians().cos()
+            / (latitude.cos() * declination.cos())
+            - latitude.tan() * declination.tan();
+        if !(-1.0..=1.0).contains(&cos_hour_angle) {
+            return None;
+        }
+        let hour_angle = cos_hour_angle.acos().to_degrees();
+
+        // Minutes after midnight UTC
+        let sunrise = 720.0 - 4.0 * (self.longitude + hour_angle) - equation_of_time;
+        let sunset = 720.0 - 4.0 * (self.longitude - hour_angle) - equation_of_time;
+
+        Some(SunTimes {
+            sunrise: to_local_time(date, sunrise)?,
+            sunset: to_local_time(date, sunset)?,
+        })
+    }
+}
+
+fn to_local_time(date: NaiveDate, utc_minutes: f64) -> Option<NaiveTime> {
+    let utc = date.and_time(NaiveTime::MIN)
+        + TimeDelta::try_seconds((utc_minutes * 60.0).round() as i64)?;
+
+    Some(Utc.from_utc_datetime(&utc).with_timezone(&Local).time())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const AMSTERDAM: Location = Location {
+        latitude: 52.37,
+        longitude: 4.89,
+    };
+
+    // The local time of a UTC time on that date
+    fn local(date: NaiveDate, hour: u32, minute: u32) -> NaiveTime {
+        let utc = date.and_hms_opt(hour, minute, 0).unwrap();
+        Utc.from_utc_datetime(&utc).with_timezone(&Local).time()
+    }
+
+    fn assert_close(actual: NaiveTime, expected: NaiveTime) {
+        let delta = (actual - expected).num_minutes().abs();
+        assert!(
+            delta <= 3 || delta >= 24 * 60 - 3,
+            "{} is not close to {}",
+            actual,
+            expected
+        );
+    }
+
+    #[test]
+    fn sun_times_match_the_almanac() {
+        // 05:18 and 22:06 CEST
+        let summer = NaiveDate::from_ymd_opt(2024, 6, 21).unwrap();
+        let sun_times = AMSTERDAM.sun_times(summer).unwrap();
+        assert_close(sun_times.sunrise, local(summer, 3, 18));
+        assert_close(sun_times.sunset, local(summer, 20, 6));
+
+        // 08:45 and 16:29 CET
+        let winter = NaiveDate::from_ymd_opt(2024, 12, 21).unwrap();
+        let sun_times = AMSTERDAM.sun_times(winter).unwrap();
+        assert_close(sun_times.sunrise, local(winter, 7, 45));
+        assert_close(sun_times.sunset, local(winter, 15, 29));
+    }
+
+    #[test]
+    fn sun_times_are_none_during_polar_day_and_night() {
+        let tromso = Location {
+            latitude: 69.65,
+            longitude: 18.96,
+        };
+
+        for (month, day) in [(6, 21), (12, 21)] {
+            let date = NaiveDate::from_ymd_opt(2024, month, day).unwrap();
+            assert!(tromso.sun_times(date).is_none());
+        }
+    }
+}
@@ -0,0 +1,33 @@
+use std::path::Path;
+
+use chrono::NaiveDateTime;
+
+use crate::models::{schedule::Schedule, slideshow::Slideshow};
+
+// What the scheduler follows, a walrust schedule or an imported slideshow
+#[derive(Debug, Clone)]
+pub enum Timetable {
+    Schedule(Schedule),
+    Slideshow(Slideshow),
+}
+
+impl Timetable {
+    // The image or directory that should be shown at that time, if any
+    pub fn active(&self, at: NaiveDateTime) -> Option<&Path> {
+        match self {
+            Self::Schedule(schedule) => schedule
+                .active(at)
+                .map(|index| schedule.entries[index].path.as_path()),
+            Self::Slideshow(slideshow) => slideshow
+                .active(at)
+                .map(|index| slideshow.slides[index].path.as_path()),
+        }
+    }
+
+    pub fn next_change(&self, after: NaiveDateTime) -> NaiveDateTime {
+        match self {
+            Self::Schedule(schedule) => schedule.next_change(after),
+            Self::Slideshow(slideshow) => slideshow.next_change(after),
+        }
+    }
+}
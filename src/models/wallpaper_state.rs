@@ -0,0 +1,101 @@
+use std::{
+    collections::{BTreeMap, VecDeque},
+    path::{Path, PathBuf},
+};
+
+use rand::seq::IndexedRandom;
+use serde::{Deserialize, Serialize};
+
+use crate::models::{image_file::ImageFile, monitor::Monitor, os_path};
+
+// Number of wallpapers kept in the history
+pub const HISTORY_CAPACITY: usize = 100;
+
+// Number of recent wallpapers a random choice avoids by default
+pub const DEFAULT_HISTORY: usize = 10;
+
+/*
+ * WallpaperState is the last wallpaper walrust set on each monitor,
+ * and the history of every wallpaper it set.
+ * Monitors are saved by their selector (see Monitor::selector),
+ * so the state still matches when connector names change.
+ */
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct WallpaperState {
+    #[serde(with = "os_path::map")]
+    pub wallpapers: BTreeMap<String, PathBuf>, // Monitor selector -> image path
+
+    #[serde(default, with = "os_path::list")]
+    pub history: VecDeque<PathBuf>, // Oldest first
+}
+
+impl WallpaperState {
+    pub fn insert(&mut self, monitor: &Monitor, image_path: PathBuf) {
+        // Drop the entry saved under the other selector of the same monitor
+        self.wallpapers
+            .retain(|selector, _| !monitor.matches(selector));
+        self.wallpapers
+            .insert(monitor.selector(), image_path.clone());
+
+        self.history.retain(|path| *path != image_path);
+        self.history.push_back(image_path);
+        if self.history.len() > HISTORY_CAPACITY {
+            self.history.pop_front();
+        }
+    }
+
+    // Whether the image is one of the last `count` wallpapers
+    pub fn is_recent(&self, image_path: &Path, count: usize) -> bool {
+        self.history
+            .iter()
+            .rev()
+            .take(count)
+            .any(|path| path == image_path)
+    }
+
+    // The images that are not one of the last `count` wallpapers
+    pub fn not_recent<'a>(&self, image_paths: &[&'a Path], count: usize) -> Vec<&'a Path> {
+        image_paths
+            .iter()
+            .copied()
+            .filter(|path| !self.is_recent(path, count))
+            .collect()
+    }
+
+    // Up to `count` different random images, leaving out corrupt ones.
+    // The last `history` wallpapers are only chosen when there are not enough
+    // other images, in which case the flag is true.
+    pub fn choose_random<'a>(
+        &self,
+        images: &'a [ImageFile],
+        count: usize,
+        history: usize,
+    ) -> (Vec<&'a Path>, bool) {
+        let image_paths: Vec<&Path> = images
+            .iter()
+            .filter(|image| !image.corrupt)
+            .map(|image| image.path.as_path())
+            .collect();
+
+        let mut candidates = self.not_recent(&image_paths, history);
+        let reused = candidates.len() < count;
+        if reused {
+            candidates = image_paths;
+        }
+
+        let chosen = candidates
+            .choose_multiple(&mut rand::rng(), count)
+            .copied()
+            .collect();
+
+        (chosen, reused)
+    }
+
+    pub fn get(&self, monitor: &Monitor) -> Option<&PathBuf> {
+        self.wallpapers
+            .iter()
+            .find(|(selector, _)| monitor.matches(selector))
+            .map(|(_, image_path)| image_path)
+    }
+}
@@ -0,0 +1,7 @@
+use anyhow::Result;
+
+use crate::models::config::Config;
+
+pub trait ConfigRepository {
+    fn load(&self) -> Result<Config>;
+}
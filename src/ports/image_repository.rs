@@ -1,8 +1,9 @@
 use anyhow::Result;
-use std::path::Path;
+use std::path::{Path, PathBuf};
 
 use crate::models::image_file::ImageFile;
 
 pub trait ImageRepository {
     fn list_images(&self, path: &Path) -> Result<Vec<ImageFile>>;
+    fn list_directories(&self, path: &Path) -> Result<Vec<PathBuf>>;
 }
@@ -3,6 +3,10 @@ use std::path::Path;
 use anyhow::Result;
 use image::DynamicImage;
 
+use crate::models::image_format::ImageFormat;
+
 pub trait ImageServicePort {
     fn decode(&self, path: &Path) -> Result<DynamicImage>;
+    fn format(&self, path: &Path) -> Option<ImageFormat>;
+    fn dimensions(&self, path: &Path) -> Result<(u32, u32)>;
 }
@@ -1,9 +1,15 @@
+pub mod config_repository;
 pub mod image_repository;
 pub mod image_service_port;
 pub mod monitor_provider;
+pub mod schedule_repository;
+pub mod state_repository;
 pub mod wallpaper_service_port;
 
+pub use config_repository::*;
 pub use image_repository::*;
 pub use image_service_port::*;
 pub use monitor_provider::*;
+pub use schedule_repository::*;
+pub use state_repository::*;
 pub use wallpaper_service_port::*;
@@ -1,3 +1,5 @@
+use crate::models::monitor::Monitor;
+
 pub trait MonitorProviderPort {
-    fn get_monitors(&self) -> anyhow::Result<Vec<String>>;
+    fn get_monitors(&self) -> anyhow::Result<Vec<Monitor>>;
 }
@@ -0,0 +1,7 @@
+use anyhow::Result;
+
+use crate::models::timetable::Timetable;
+
+pub trait ScheduleRepository {
+    fn load(&self) -> Result<Timetable>;
+}
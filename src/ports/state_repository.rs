@@ -0,0 +1,20 @@
+use std::path::Path;
+
+use anyhow::{Context, Result};
+
+use crate::models::{monitor::Monitor, wallpaper_state::WallpaperState};
+
+pub trait StateRepository {
+    fn load(&self) -> Result<WallpaperState>;
+    fn save(&self, state: &WallpaperState) -> Result<()>;
+
+    // Saves the wallpaper that was just set on the monitor.
+    // A state that can't be loaded is left alone rather than overwritten.
+    fn record(&self, monitor: &Monitor, image_path: &Path) -> Result<()> {
+        let mut state = self
+            .load()
+            .context("The wallpaper was set, but it couldn't be saved")?;
+        state.insert(monitor, image_path.to_path_buf());
+        self.save(&state)
+    }
+}
@@ -2,6 +2,9 @@ use std::path::Path;
 
 use anyhow::Result;
 
+use crate::models::{image_format::ImageFormat, monitor::Monitor};
+
 pub trait WallpaperServicePort {
-    fn set_wallpaper(&self, monitor_name: &str, path: &Path) -> Result<()>;
+    fn set_wallpaper(&self, monitor: &Monitor, path: &Path) -> Result<()>;
+    fn supported_formats(&self) -> &'static [ImageFormat];
 }
@@ -3,18 +3,24 @@ use std::path::PathBuf;
 use anyhow::{Context, Ok, Result};
 use ratatui::{
     DefaultTerminal, Frame,
-    crossterm::event::{self, KeyCode, KeyEvent},
+    crossterm::event::{self, KeyEvent},
     layout::{Constraint, Layout},
+    style::Style,
     text::Line,
     widgets::Block,
 };
 use ratatui_image::picker::Picker;
 
 use crate::{
-    adapters::{ImageDiskRepository, ImageService, WallpaperService},
-    ports::WallpaperServicePort,
+    adapters::{ImageDiskRepository, ImageService, StateFileRepository, WallpaperService},
+    models::{
+        config::{Config, ThemeConfig},
+        monitor::Monitor,
+    },
+    ports::{StateRepository, WallpaperServicePort},
     tui::{
         PreviewComponent, WallpaperListComponent,
+        keymap::{Action, Keymap},
         messages::{Message, MessageState, Messages},
     },
 };
@@ -23,14 +29,18 @@ pub struct App {
     // Dependencies
     messages: Messages,
     wallpaper_service: WallpaperService,
+    state_repository: StateFileRepository,
 
     // Components
-    wallpaper_list_component: WallpaperListComponent,
+    wallpaper_list_component: WallpaperListComponent<ImageDiskRepository>,
     preview_component: PreviewComponent<ImageService>,
 
     // Data or states
-    monitors: Vec<String>,
+    monitors: Vec<Monitor>,
     selected_monitor: usize,
+    preview_width: u16,
+    keymap: Keymap,
+    theme: ThemeConfig,
     quit: bool,
 }
 
@@ -38,25 +48,42 @@ impl App {
     pub fn new(
         mut messages: Messages,
         dir_path: PathBuf,
-        monitors: Vec<String>,
+        monitors: Vec<Monitor>,
         picker: Picker,
         wallpaper_service: WallpaperService,
+        state_repository: StateFileRepository,
+        config: &Config,
     ) -> Result<Self> {
-        let wallpaper_list_component =
-            WallpaperListComponent::new(ImageDiskRepository::default(), dir_path)
-                .with_context(|| "Failed to create wallpaper list component")?;
-        let preview_component = PreviewComponent::new(picker, &messages, ImageService {})?;
+        let keymap = Keymap::new(&config.keybindings);
+        let wallpaper_list_component = WallpaperListComponent::new(
+            ImageDiskRepository::new(&config.images),
+            dir_path,
+            keymap.clone(),
+            config.theme.clone(),
+            wallpaper_service.supported_formats(),
+        )
+        .with_context(|| "Failed to create wallpaper list component")?;
+        let preview_component = PreviewComponent::new(
+            picker,
+            &messages,
+            ImageService {},
+            config.tui.preview_cache_size,
+        )?;
 
         messages.start_event_listener();
 
         Ok(App {
             selected_monitor: 0,
+            preview_width: config.tui.preview_width,
+            keymap,
+            theme: config.theme.clone(),
             quit: false,
             messages,
             monitors,
             wallpaper_list_component,
             preview_component,
             wallpaper_service,
+            state_repository,
         })
     }
 
@@ -91,12 +118,17 @@ impl App {
             .areas(frame.area());
         let border_widget = Block::bordered()
             .border_type(ratatui::widgets::BorderType::Rounded)
-            .title(Line::from(&*self.monitors[self.selected_monitor]).centered());
+            .border_style(Style::new().fg(self.theme.border_color()))
+            .title_style(Style::new().fg(self.theme.title_color()))
+            .title(Line::from(self.monitors[self.selected_monitor].to_string()).centered())
+            .title_bottom(Line::from(self.formats_title()).centered());
 
         let inner_area = border_widget.inner(bordered_area);
-        let [preview_area, list_area] =
-            Layout::horizontal([Constraint::Percentage(40), Constraint::Percentage(60)])
-                .areas(inner_area);
+        let [preview_area, list_area] = Layout::horizontal([
+            Constraint::Percentage(self.preview_width),
+            Constraint::Percentage(100 - self.preview_width),
+        ])
+        .areas(inner_area);
 
         frame.render_widget("Select wallpaper", frame.area());
         frame.render_widget(&border_widget, bordered_area);
@@ -111,41 +143,50 @@ impl App {
                     .insert_protocol(image_path, *protocol)?;
                 Ok(MessageState::Consumed)
             }
+            Message::ImagePreviewFailed(image_path, error) => {
+                self.wallpaper_list_component.mark_corrupt(&image_path);
+                self.preview_component.insert_error(image_path, error);
+                Ok(MessageState::Consumed)
+            }
             Message::Key(key) => self.event(key),
             Message::Resize => Ok(MessageState::Consumed),
         }
     }
 
     fn event(&mut self, key: KeyEvent) -> Result<MessageState> {
-        match key.code {
-            KeyCode::Esc | KeyCode::Char('q') => {
-                self.quit = true;
-                Ok(MessageState::Consumed)
-            }
-            KeyCode::Right | KeyCode::Char('l') => {
-                self.select_next();
-                Ok(MessageState::Consumed)
-            }
-            KeyCode::Left | KeyCode::Char('h') => {
-                self.select_previous();
-                Ok(MessageState::Consumed)
+        // Keys typed into a search belong to the list, even q or Enter,
+        // and Esc clears a search before it can quit
+        if self.wallpaper_list_component.captures(&key) {
+            self.components_event(key)?;
+            return Ok(MessageState::NotConsumed);
+        }
+
+        match self.keymap.action(key) {
+            Some(Action::Quit) => self.quit = true,
+            Some(Action::NextMonitor) => self.select_next(),
+            Some(Action::PreviousMonitor) => self.select_previous(),
+            // Enter on a directory opens it instead
+            Some(Action::SetWallpaper)
+                if self.wallpaper_list_component.selected_directory().is_none() =>
+            {
+                self.change_wallpaper()?
             }
-            KeyCode::Enter => {
-                self.change_wallpaper()?;
-                Ok(MessageState::Consumed)
+            _ => {
+                self.components_event(key)?;
             }
-            _ => self.components_event(key),
-        }?;
+        }
 
         Ok(MessageState::NotConsumed)
     }
 
     fn components_event(&mut self, key: event::KeyEvent) -> Result<MessageState> {
-        if self.wallpaper_list_component.event(key)?.is_consumed()
-            && let Some(image_path) = self.wallpaper_list_component.get_selected()
-        {
-            self.preview_component
-                .update_image_path(image_path.to_path_buf())?;
+        if self.wallpaper_list_component.event(key)?.is_consumed() {
+            match self.wallpaper_list_component.get_selected() {
+                Some(image_path) => self
+                    .preview_component
+                    .update_image_path(image_path.to_path_buf())?,
+                None => self.preview_component.clear(),
+            }
         }
 
         Ok(MessageState::Consumed)
@@ -153,13 +194,26 @@ impl App {
 
     fn change_wallpaper(&self) -> Result<()> {
         if let Some(image_path) = self.wallpaper_list_component.get_selected() {
-            self.wallpaper_service
-                .set_wallpaper(&self.monitors[self.selected_monitor], image_path)?
+            let monitor = &self.monitors[self.selected_monitor];
+            self.wallpaper_service.set_wallpaper(monitor, image_path)?;
+            self.state_repository.record(monitor, image_path)?;
         }
 
         Ok(())
     }
 
+    // Other formats are converted to PNG when they're set
+    fn formats_title(&self) -> String {
+        let formats: Vec<String> = self
+            .wallpaper_service
+            .supported_formats()
+            .iter()
+            .map(|format| format.to_string())
+            .collect();
+
+        format!("Backend displays {}", formats.join(", "))
+    }
+
     fn select_next(&mut self) {
         if self.selected_monitor == self.monitors.len() - 1 {
             self.selected_monitor = 0;
@@ -1,5 +1,5 @@
 use std::{
-    collections::HashSet,
+    collections::{HashMap, HashSet},
     num::NonZeroUsize,
     path::PathBuf,
     sync::mpsc::{self, Receiver, Sender},
@@ -8,7 +8,11 @@ use std::{
 
 use anyhow::Result;
 use lru::LruCache;
-use ratatui::{Frame, layout::Rect, widgets::Block};
+use ratatui::{
+    Frame,
+    layout::Rect,
+    widgets::{Block, Paragraph, Wrap},
+};
 use ratatui_image::{StatefulImage, picker::Picker, protocol::StatefulProtocol};
 
 use crate::{
@@ -29,6 +33,7 @@ pub struct PreviewComponent<A> {
     // Data or states
     image_path: PathBuf,
     protocols: LruCache<PathBuf, StatefulProtocol>, // Protocol cache
+    errors: HashMap<PathBuf, String>,               // Images that failed to decode
 
     // Concurrency
     image_path_tx: Sender<PathBuf>,
@@ -39,23 +44,31 @@ impl<A> PreviewComponent<A>
 where
     A: ImageServicePort + Clone + Send + 'static,
 {
-    pub fn new(picker: Picker, messages: &Messages, image_service: A) -> Result<Self> {
+    pub fn new(
+        picker: Picker,
+        messages: &Messages,
+        image_service: A,
+        cache_size: usize,
+    ) -> Result<Self> {
         let image_path_channel = mpsc::channel::<PathBuf>();
 
+        let preview = PreviewComponent {
+            image_service,
+            image_path: PathBuf::new(),
+            protocols: LruCache::new(NonZeroUsize::new(cache_size).unwrap_or(NonZeroUsize::MIN)),
+            errors: HashMap::new(),
+            image_path_tx: image_path_channel.0,
+            pending_image_previews: HashSet::new(),
+        };
+
         Self::spawn_path_request_listener(
-            image_service.clone(),
+            preview.image_service.clone(),
             image_path_channel.1,
             messages.tx.clone(),
             picker,
         )?;
 
-        Ok(PreviewComponent {
-            image_service,
-            image_path: PathBuf::new(),
-            protocols: LruCache::new(NonZeroUsize::new(8).unwrap()),
-            image_path_tx: image_path_channel.0,
-            pending_image_previews: HashSet::new(),
-        })
+        Ok(preview)
     }
 
     pub fn init(&mut self, selected_image_path: PathBuf) -> Result<()> {
@@ -72,6 +85,9 @@ where
 
         if let Some(protocol) = self.protocols.get_mut(&self.image_path) {
             frame.render_stateful_widget(StatefulImage::new(), block_inner_area, protocol);
+        } else if let Some(error) = self.errors.get(&self.image_path) {
+            let error_widget = Paragraph::new(error.as_str()).wrap(Wrap { trim: true });
+            frame.render_widget(error_widget, block_inner_area);
         }
     }
 
@@ -89,12 +105,18 @@ where
         Ok(())
     }
 
+    pub fn insert_error(&mut self, image: PathBuf, error: String) {
+        self.pending_image_previews.remove(&image);
+        self.errors.insert(image, error);
+    }
+
     pub fn update_image_path(&mut self, new_image_path: PathBuf) -> Result<()> {
         if self.image_path == new_image_path {
             return Ok(());
         }
 
         if !self.protocols.contains(&new_image_path)
+            && !self.errors.contains_key(&new_image_path)
             && !self.pending_image_previews.contains(&new_image_path)
         {
             self.image_path_tx.send(new_image_path.clone())?;
@@ -105,6 +127,11 @@ where
         Ok(())
     }
 
+    // Nothing is previewed, e.g. when a directory is selected
+    pub fn clear(&mut self) {
+        self.image_path = PathBuf::new();
+    }
+
     // TODO:
     // I'm planning to add a compression mechanism.
     // Currently this method decodes the actual image (not a compressed one)
@@ -118,19 +145,16 @@ where
     ) -> anyhow::Result<()> {
         thread::spawn(move || {
             while let Ok(image_path) = image_path_rx.recv() {
-                let dyn_image = image_service.decode(&image_path);
-                if dyn_image.is_err() {
-                    break;
-                }
-
-                let protocol = picker.new_resize_protocol(dyn_image.unwrap());
-
-                image_preview_tx
-                    .send(Message::ImagePreviewFinished(
+                // A broken image is reported, the next ones are still previewed
+                let message = match image_service.decode(&image_path) {
+                    Ok(dyn_image) => Message::ImagePreviewFinished(
                         image_path,
-                        Box::new(protocol),
-                    ))
-                    .unwrap();
+                        Box::new(picker.new_resize_protocol(dyn_image)),
+                    ),
+                    Err(error) => Message::ImagePreviewFailed(image_path, format!("{:#}", error)),
+                };
+
+                image_preview_tx.send(message).unwrap();
             }
         });
 
@@ -1,64 +1,506 @@
-use std::path::{Path, PathBuf};
+use std::{
+    env,
+    path::{Path, PathBuf},
+};
 
-use anyhow::{Ok, Result};
+use anyhow::{Context, Ok, Result};
+use fuzzy_matcher::{FuzzyMatcher, skim::SkimMatcherV2};
 use ratatui::{
     Frame,
     crossterm::event::{self, KeyCode},
-    layout::Rect,
+    layout::{Constraint, Layout, Rect},
+    style::{Modifier, Style},
+    text::{Line, Span},
     widgets::{Block, Borders, List, ListItem, ListState},
 };
 
-use crate::{models::image_file::ImageFile, ports::ImageRepository, tui::messages::MessageState};
+use crate::{
+    models::{config::ThemeConfig, image_file::ImageFile, image_format::ImageFormat},
+    ports::ImageRepository,
+    tui::{
+        keymap::{Action, Keymap},
+        messages::MessageState,
+    },
+};
+
+/*
+ * WallpaperListComponent lists the subdirectories and the images of the directory.
+ * Opening a subdirectory or going to the parent lists that directory instead,
+ * and the border title shows where the list is. A directory that can't be opened
+ * keeps the current one listed and shows why below the list.
+ * The list can be searched, which highlights the matches and jumps between them,
+ * or filtered, which only shows the matches sorted by how well they match.
+ * Both match the image names fuzzily.
+ */
+
+#[derive(PartialEq, Eq)]
+enum QueryKind {
+    Search,
+    Filter,
+}
+
+struct Query {
+    kind: QueryKind,
+    text: String,
+    typing: bool, // Keys are typed into the query until Enter or Esc
+}
+
+// An image that matches the query
+struct Match {
+    image_index: usize,
+    score: i64,
+    char_indices: Vec<usize>, // Matched characters of the name, for highlighting
+}
+
+pub struct WallpaperListComponent<R> {
+    // Dependencies
+    image_repository: R,
+    matcher: SkimMatcherV2,
 
-pub struct WallpaperListComponent {
     // Data or states
+    dir_path: PathBuf,
+    directories: Vec<PathBuf>, // Listed before the images, hidden while filtering
     pub images: Vec<ImageFile>,
+    visible: Vec<usize>, // Indexes into images of the listed images
+    matches: Vec<Match>, // Sorted by image index
+    query: Option<Query>,
+    error: Option<String>, // Why the last directory couldn't be opened
     list_state: ListState,
+    keymap: Keymap,
+    theme: ThemeConfig,
+    supported_formats: &'static [ImageFormat], // Formats the backend displays without converting
 }
 
-impl WallpaperListComponent {
-    pub fn new<R: ImageRepository>(image_repository: R, dir_path: PathBuf) -> anyhow::Result<Self> {
-        let images = image_repository.list_images(&dir_path)?;
-        let mut list_state = ListState::default();
+impl<R: ImageRepository> WallpaperListComponent<R> {
+    pub fn new(
+        image_repository: R,
+        dir_path: PathBuf,
+        keymap: Keymap,
+        theme: ThemeConfig,
+        supported_formats: &'static [ImageFormat],
+    ) -> anyhow::Result<Self> {
+        // Absolute, so the parents can be reached
+        let dir_path = dir_path
+            .canonicalize()
+            .with_context(|| format!("Failed to open {:?}", dir_path))?;
 
-        list_state.select_first();
+        let mut wallpaper_list_component = Self {
+            image_repository,
+            matcher: SkimMatcherV2::default().smart_case(),
+            dir_path: PathBuf::new(),
+            directories: Vec::new(),
+            images: Vec::new(),
+            visible: Vec::new(),
+            matches: Vec::new(),
+            query: None,
+            error: None,
+            list_state: ListState::default(),
+            keymap,
+            theme,
+            supported_formats,
+        };
+        wallpaper_list_component.change_directory(dir_path)?;
 
-        Ok(Self { images, list_state })
+        Ok(wallpaper_list_component)
     }
 
     pub fn render(&mut self, frame: &mut Frame, area: Rect) {
-        let list_item: Vec<ListItem> = self
-            .images
+        let directory_style = Style::new().add_modifier(Modifier::BOLD);
+        let directory_items = self.directories[..self.listed_directory_count()]
+            .iter()
+            .map(|dir_path| {
+                let name = dir_path
+                    .file_name()
+                    .map(|name| name.to_string_lossy())
+                    .unwrap_or_default();
+                ListItem::from(Span::styled(format!("{}/", name), directory_style))
+            });
+        let image_items = self
+            .visible
             .iter()
-            .map(|i| ListItem::from(i.name.clone()))
-            .collect();
-        let wallpaper_list_widget = List::new(list_item).highlight_symbol("> ");
-        let block_widget = Block::bordered().borders(Borders::LEFT);
+            .map(|&image_index| ListItem::from(self.highlighted_name(image_index)));
+        let list_item: Vec<ListItem> = directory_items.chain(image_items).collect();
+        let wallpaper_list_widget = List::new(list_item)
+            .highlight_symbol(self.theme.highlight_symbol.as_str())
+            .highlight_style(Style::new().fg(self.theme.selected_color()));
+        let mut block_widget = Block::bordered()
+            .borders(Borders::LEFT)
+            .title_style(Style::new().fg(self.theme.title_color()))
+            .title(breadcrumb(&self.dir_path));
+        if let Some(error) = &self.error {
+            block_widget = block_widget.title_bottom(Line::styled(
+                error.as_str(),
+                Style::new().fg(self.theme.corrupt_color()),
+            ));
+        }
 
-        let list_area = block_widget.inner(area);
+        let inner_area = block_widget.inner(area);
+        let [list_area, query_area] = match self.query {
+            Some(_) => Layout::vertical([Constraint::Fill(1), Constraint::Length(1)]),
+            None => Layout::vertical([Constraint::Fill(1), Constraint::Length(0)]),
+        }
+        .areas(inner_area);
 
         frame.render_widget(block_widget, area);
         frame.render_stateful_widget(wallpaper_list_widget, list_area, &mut self.list_state);
+
+        if let Some(query) = &self.query {
+            let prefix = match query.kind {
+                QueryKind::Search => "/",
+                QueryKind::Filter => "Filter: ",
+            };
+            let cursor = if query.typing { "_" } else { "" };
+            let line = format!(
+                "{}{}{} ({}/{})",
+                prefix,
+                query.text,
+                cursor,
+                self.matches.len(),
+                self.images.len()
+            );
+
+            frame.render_widget(line, query_area);
+        }
     }
 
     pub fn event(&mut self, key: event::KeyEvent) -> Result<MessageState> {
+        if self.is_typing() {
+            return self.query_event(key);
+        }
+
+        // The error is shown until the next key
+        self.error = None;
+
+        // Esc clears a confirmed search or filter too
+        if key.code == KeyCode::Esc && self.query.is_some() {
+            self.clear_query();
+            return Ok(MessageState::Consumed);
+        }
+
+        match self.keymap.action(key) {
+            Some(Action::NextImage) => self.list_state.select_next(),
+            Some(Action::PreviousImage) => self.list_state.select_previous(),
+            Some(Action::Search) => self.start_query(QueryKind::Search),
+            Some(Action::Filter) => self.start_query(QueryKind::Filter),
+            Some(Action::NextMatch) => self.select_match(true),
+            Some(Action::PreviousMatch) => self.select_match(false),
+            Some(Action::SetWallpaper) => match self.selected_directory() {
+                Some(dir_path) => {
+                    let result = self.change_directory(dir_path.to_path_buf());
+                    self.show_error(result);
+                }
+                None => return Ok(MessageState::NotConsumed),
+            },
+            Some(Action::ParentDirectory) => {
+                let result = self.open_parent();
+                self.show_error(result);
+            }
+            _ => return Ok(MessageState::NotConsumed),
+        }
+
+        Ok(MessageState::Consumed)
+    }
+
+    // While typing, every key belongs to the query
+    fn is_typing(&self) -> bool {
+        self.query.as_ref().is_some_and(|query| query.typing)
+    }
+
+    // Keys the list handles before the app keybindings
+    pub fn captures(&self, key: &event::KeyEvent) -> bool {
+        self.is_typing() || (key.code == KeyCode::Esc && self.query.is_some())
+    }
+
+    pub fn get_selected(&self) -> Option<&Path> {
+        self.selected_image_index()
+            .and_then(|image_index| self.images.get(image_index))
+            .map(|image_file| image_file.path.as_path())
+    }
+
+    pub fn selected_directory(&self) -> Option<&Path> {
+        self.list_state
+            .selected()
+            .filter(|&index| index < self.listed_directory_count())
+            .map(|index| self.directories[index].as_path())
+    }
+
+    // Lists another directory, the query doesn't carry over.
+    // Nothing changes when the directory can't be listed.
+    fn change_directory(&mut self, dir_path: PathBuf) -> Result<()> {
+        let images = self
+            .image_repository
+            .list_images(&dir_path)
+            .with_context(|| format!("Failed to open {:?}", dir_path))?;
+        let directories = self
+            .image_repository
+            .list_directories(&dir_path)
+            .with_context(|| format!("Failed to open {:?}", dir_path))?;
+
+        self.query = None;
+        self.matches.clear();
+        self.visible = (0..images.len()).collect();
+        self.images = images;
+        self.directories = directories;
+        self.dir_path = dir_path;
+        self.list_state.select_first();
+
+        Ok(())
+    }
+
+    // Flags an image that turned out to be broken, e.g. when previewing it
+    pub fn mark_corrupt(&mut self, image_path: &Path) {
+        if let Some(image) = self
+            .images
+            .iter_mut()
+            .find(|image| image.path == image_path)
+        {
+            image.corrupt = true;
+        }
+    }
+
+    // Selects the directory that was left
+    fn open_parent(&mut self) -> Result<()> {
+        let Some(parent) = self.dir_path.parent().map(Path::to_path_buf) else {
+            return Ok(());
+        };
+        let previous = self.dir_path.clone();
+
+        self.change_directory(parent)?;
+
+        if let Some(index) = self.directories.iter().position(|dir| *dir == previous) {
+            self.list_state.select(Some(index));
+        }
+
+        Ok(())
+    }
+
+    // Browsing errors are shown instead of closing the TUI
+    fn show_error(&mut self, result: Result<()>) {
+        self.error = result.err().map(|error| format!("{:#}", error));
+    }
+
+    // Directories aren't matched, so filtering hides them
+    fn listed_directory_count(&self) -> usize {
+        match &self.query {
+            Some(query) if query.kind == QueryKind::Filter => 0,
+            _ => self.directories.len(),
+        }
+    }
+
+    fn query_event(&mut self, key: event::KeyEvent) -> Result<MessageState> {
+        let Some(query) = &mut self.query else {
+            return Ok(MessageState::NotConsumed);
+        };
+
         match key.code {
-            KeyCode::Down | KeyCode::Char('j') => {
-                self.list_state.select_next();
-                Ok(MessageState::Consumed)
+            KeyCode::Enter => query.typing = false,
+            KeyCode::Esc => {
+                self.clear_query();
+                return Ok(MessageState::Consumed);
             }
-            KeyCode::Up | KeyCode::Char('k') => {
-                self.list_state.select_previous();
-                Ok(MessageState::Consumed)
+            KeyCode::Backspace => {
+                query.text.pop();
             }
-            _ => Ok(MessageState::NotConsumed),
+            KeyCode::Char(char) => query.text.push(char),
+            _ => return Ok(MessageState::NotConsumed),
         }
+
+        self.update_matches();
+
+        Ok(MessageState::Consumed)
     }
 
-    pub fn get_selected(&self) -> Option<&Path> {
+    fn start_query(&mut self, kind: QueryKind) {
+        self.clear_query();
+
+        // Filtering hides the directories, which moves the images up
+        let selected = self.selected_image_index();
+        self.query = Some(Query {
+            kind,
+            text: String::new(),
+            typing: true,
+        });
+        self.select_image_index(selected);
+    }
+
+    // Shows every image again, keeping the selected one
+    fn clear_query(&mut self) {
+        let selected = self.selected_image_index();
+
+        self.query = None;
+        self.matches.clear();
+        self.visible = (0..self.images.len()).collect();
+        self.select_image_index(selected);
+    }
+
+    fn update_matches(&mut self) {
+        let Some(query) = &self.query else {
+            return;
+        };
+
+        let selected = self.selected_image_index();
+
+        self.matches = if query.text.is_empty() {
+            Vec::new()
+        } else {
+            self.images
+                .iter()
+                .enumerate()
+                .filter_map(|(image_index, image)| {
+                    let (score, char_indices) = self
+                        .matcher
+                        .fuzzy_indices(&image.display_name(), &query.text)?;
+
+                    Some(Match {
+                        image_index,
+                        score,
+                        char_indices,
+                    })
+                })
+                .collect()
+        };
+
+        match query.kind {
+            // Jump to the first match from the selected image on
+            QueryKind::Search => {
+                let selected = selected.unwrap_or(0);
+                let next = self
+                    .matches
+                    .iter()
+                    .find(|m| m.image_index >= selected)
+                    .or(self.matches.first())
+                    .map(|m| m.image_index);
+                self.select_image_index(next.or(Some(selected)));
+            }
+            // Only the matches, the best ones first
+            QueryKind::Filter => {
+                if query.text.is_empty() {
+                    self.visible = (0..self.images.len()).collect();
+                } else {
+                    let mut ranked: Vec<&Match> = self.matches.iter().collect();
+                    ranked.sort_by_key(|m| std::cmp::Reverse(m.score));
+                    self.visible = ranked.iter().map(|m| m.image_index).collect();
+                }
+                self.list_state.select_first();
+            }
+        }
+    }
+
+    // Moves the selection to the next (or previous) visible match, wrapping around
+    fn select_match(&mut self, forward: bool) {
+        if self.matches.is_empty() {
+            return;
+        }
+
+        let offset = self.listed_directory_count();
+        let length = self.visible.len();
+        // From a directory, the first image is the next one
+        let selected = match self.list_state.selected().unwrap_or(0).checked_sub(offset) {
+            Some(selected) => selected,
+            None if forward => length - 1,
+            None => 0,
+        };
+
+        for step in 1..=length {
+            let index = if forward {
+                (selected + step) % length
+            } else {
+                (selected + length - step % length) % length
+            };
+
+            if self.match_of(self.visible[index]).is_some() {
+                self.list_state.select(Some(offset + index));
+                return;
+            }
+        }
+    }
+
+    fn match_of(&self, image_index: usize) -> Option<&Match> {
+        self.matches
+            .binary_search_by_key(&image_index, |m| m.image_index)
+            .ok()
+            .map(|index| &self.matches[index])
+    }
+
+    fn selected_image_index(&self) -> Option<usize> {
         self.list_state
             .selected()
-            .and_then(|index| self.images.get(index))
-            .map(|image_file| image_file.path.as_path())
+            .and_then(|index| index.checked_sub(self.listed_directory_count()))
+            .and_then(|index| self.visible.get(index))
+            .copied()
     }
+
+    fn select_image_index(&mut self, image_index: Option<usize>) {
+        let index = image_index
+            .and_then(|image_index| self.visible.iter().position(|&i| i == image_index))
+            .map(|index| self.listed_directory_count() + index)
+            .unwrap_or(0);
+
+        self.list_state.select(Some(index));
+    }
+
+    // Broken images are flagged in the corrupt color,
+    // and the ones the backend can't display are flagged as converted
+    fn highlighted_name(&self, image_index: usize) -> Line<'static> {
+        let image = &self.images[image_index];
+        let name = image.display_name();
+        let name_style = if image.corrupt {
+            Style::new().fg(self.theme.corrupt_color())
+        } else {
+            Style::new()
+        };
+        let matched_style = Style::new()
+            .fg(self.theme.matched_color())
+            .add_modifier(Modifier::BOLD);
+
+        let mut spans: Vec<Span> = match self.match_of(image_index) {
+            Some(image_match) => name
+                .chars()
+                .enumerate()
+                .map(|(char_index, char)| {
+                    if image_match.char_indices.contains(&char_index) {
+                        Span::styled(char.to_string(), matched_style)
+                    } else {
+                        Span::styled(char.to_string(), name_style)
+                    }
+                })
+                .collect(),
+            None => vec![Span::styled(name.into_owned(), name_style)],
+        };
+
+        if image.corrupt {
+            spans.push(Span::styled(" (corrupt)", name_style));
+        } else if image
+            .format
+            .is_some_and(|format| !self.supported_formats.contains(&format))
+        {
+            spans.push(Span::styled(
+                " (as PNG)",
+                Style::new().add_modifier(Modifier::DIM),
+            ));
+        }
+
+        Line::from(spans)
+    }
+}
+
+// The directory as "~ › pictures › wallpapers"
+fn breadcrumb(dir_path: &Path) -> String {
+    let home = env::var_os("HOME").map(PathBuf::from);
+    let (start, rest) = match home
+        .as_deref()
+        .and_then(|home| dir_path.strip_prefix(home).ok())
+    {
+        Some(rest) => ("~".to_string(), rest),
+        None => (
+            "/".to_string(),
+            dir_path.strip_prefix("/").unwrap_or(dir_path),
+        ),
+    };
+
+    rest.iter()
+        .map(|component| component.to_string_lossy().into_owned())
+        .fold(start, |breadcrumb, component| {
+            format!("{} › {}", breadcrumb, component)
+        })
 }
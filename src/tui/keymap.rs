@@ -0,0 +1,63 @@
+use std::collections::HashMap;
+
+use ratatui::crossterm::event::KeyEvent;
+
+use crate::models::{config::KeybindingsConfig, key_chord::KeyChord};
+
+// Everything a key can be bound to
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Action {
+    Quit,
+    NextMonitor,
+    PreviousMonitor,
+    NextImage,
+    PreviousImage,
+    SetWallpaper,
+    Search,
+    Filter,
+    NextMatch,
+    PreviousMatch,
+    ParentDirectory,
+}
+
+/*
+ * Keymap maps key chords to actions, so components match actions
+ * instead of hard-coded keys.
+ * The keybindings are validated when the config is loaded,
+ * invalid keys are ignored here.
+ */
+
+#[derive(Clone)]
+pub struct Keymap {
+    bindings: HashMap<KeyChord, Action>,
+}
+
+impl Keymap {
+    pub fn new(keybindings: &KeybindingsConfig) -> Self {
+        let mut bindings = HashMap::new();
+
+        for (action, keys) in [
+            (Action::Quit, &keybindings.quit),
+            (Action::NextMonitor, &keybindings.next_monitor),
+            (Action::PreviousMonitor, &keybindings.previous_monitor),
+            (Action::NextImage, &keybindings.next_image),
+            (Action::PreviousImage, &keybindings.previous_image),
+            (Action::SetWallpaper, &keybindings.set_wallpaper),
+            (Action::Search, &keybindings.search),
+            (Action::Filter, &keybindings.filter),
+            (Action::NextMatch, &keybindings.next_match),
+            (Action::PreviousMatch, &keybindings.previous_match),
+            (Action::ParentDirectory, &keybindings.parent_directory),
+        ] {
+            for chord in keys.iter().filter_map(|key| key.parse::<KeyChord>().ok()) {
+                bindings.insert(chord, action);
+            }
+        }
+
+        Keymap { bindings }
+    }
+
+    pub fn action(&self, key: KeyEvent) -> Option<Action> {
+        self.bindings.get(&KeyChord::from(key)).copied()
+    }
+}
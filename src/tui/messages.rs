@@ -1,6 +1,6 @@
 use std::{path::PathBuf, sync::mpsc, thread, time::Duration};
 
-use ratatui::crossterm::event::{self, Event, KeyEvent};
+use ratatui::crossterm::event::{self, Event, KeyEvent, KeyEventKind};
 use ratatui_image::protocol::StatefulProtocol;
 
 /*
@@ -18,6 +18,9 @@ pub enum Message {
     // This will be sent when a PreviewComponent worker finished generating an image protocol.
     // The StatefulProtocol is wrapped in a Box based on clippy suggestion
     ImagePreviewFinished(PathBuf, Box<StatefulProtocol>),
+
+    // Sent instead when the image can't be decoded, with the error message
+    ImagePreviewFailed(PathBuf, String),
 }
 
 #[derive(PartialEq, Eq)]
@@ -55,10 +58,13 @@ impl Messages {
 
         thread::spawn(move || {
             loop {
-                // I'm planning to add a support for control modifier if needed.
+                // Modifiers are kept in the KeyEvent, see tui::keymap.
+                // Releases are only reported by some terminals, so they're ignored.
                 if event::poll(tick_rate).unwrap() {
                     match event::read().unwrap() {
-                        Event::Key(event) => tx.send(Message::Key(event)).unwrap(),
+                        Event::Key(event) if event.kind == KeyEventKind::Press => {
+                            tx.send(Message::Key(event)).unwrap()
+                        }
                         Event::Resize(_, _) => tx.send(Message::Resize).unwrap(),
                         _ => {}
                     }
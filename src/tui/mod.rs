@@ -1,5 +1,6 @@
 pub mod app;
 pub mod components;
+pub mod keymap;
 pub mod messages;
 
 pub use components::*;